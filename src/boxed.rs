@@ -0,0 +1,72 @@
+// Rx -- Reactive programming for Rust
+// Copyright 2016 Ruud van Asseldonk
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// A copy of the License has been included in the root of the repository.
+
+use observable::Observable;
+use observer::{BoxedObserver, Observer};
+use subscription::Subscription;
+
+/// Trait that enables using `Observable` as a trait object.
+///
+/// The `subscribe()` method cannot be called on a trait object, because it is
+/// generic over the observer. This trait takes the observer as a trait object
+/// instead, and it erases the type of the subscription. It is implemented
+/// automatically for every observable.
+pub trait ObservableBox<'a, T, E> {
+    /// As `subscribe()`, but takes the observer as box so it can be called on a trait object.
+    fn subscribe_box<'o>(&mut self, observer: Box<BoxedObserver<T, E> + 'o>) -> BoxSubscription<'a>;
+}
+
+impl<'a, Ob> ObservableBox<'a, Ob::Item, Ob::Error> for Ob where Ob: Observable + 'a {
+    fn subscribe_box<'o>(&mut self,
+                         observer: Box<BoxedObserver<Ob::Item, Ob::Error> + 'o>)
+                         -> BoxSubscription<'a> {
+        BoxSubscription {
+            subscription: Box::new(self.subscribe(observer)),
+        }
+    }
+}
+
+/// The result of subscribing to a boxed observable.
+///
+/// This subscription owns the subscription of the underlying observable, and
+/// dropping it drops that subscription.
+pub struct BoxSubscription<'a> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subscription: Box<Subscription + 'a>,
+}
+
+impl<'a> Subscription for BoxSubscription<'a> { }
+
+/// An observable of which the concrete type has been erased.
+///
+/// This is the result of calling `boxed()` on an observable. It can be used to
+/// store observables of different types in the same place, at the cost of a
+/// heap allocation per subscription and virtual calls.
+pub struct BoxObservable<'a, T, E> {
+    observable: Box<ObservableBox<'a, T, E> + 'a>,
+}
+
+impl<'a, T, E> BoxObservable<'a, T, E> {
+    /// Erases the type of the observable, see also `Observable::boxed()`.
+    pub fn new<Ob>(observable: Ob) -> BoxObservable<'a, T, E>
+        where Ob: Observable<Item = T, Error = E> + 'a {
+        BoxObservable {
+            observable: Box::new(observable),
+        }
+    }
+}
+
+impl<'a, T: Clone, E: Clone> Observable for BoxObservable<'a, T, E> {
+    type Item = T;
+    type Error = E;
+    type Subscription = BoxSubscription<'a>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.observable.subscribe_box(Box::new(observer))
+    }
+}
@@ -5,9 +5,12 @@
 // you may not use this file except in compliance with the License.
 // A copy of the License has been included in the root of the repository.
 
-use observable::Observable;
+use observable::{ImmutableObservable, Observable};
 use observer::Observer;
 use std::marker::PhantomData;
+use std::ops::Range;
+use subscription::Subscription;
+use UncancellableSubscription;
 
 /// An observable that never pushes a value and never completes.
 pub struct Never<T: Clone, E: Clone> {
@@ -21,6 +24,8 @@ pub struct Never<T: Clone, E: Clone> {
 /// never pushes a value anyway.
 pub struct NeverSubscription;
 
+impl Subscription for NeverSubscription { }
+
 impl<T: Clone, E: Clone> Never<T, E> {
     /// Creates an observable that never pushes a value and never completes.
     pub fn new() -> Never<T, E> {
@@ -31,20 +36,326 @@ impl<T: Clone, E: Clone> Never<T, E> {
     }
 }
 
+/// Creates an observable that never pushes a value and never completes.
+///
+/// This is the same as `Never::new()`. It is useful as a neutral operand of
+/// combinators: merging with it does not add values, but the merged
+/// observable never completes. Dropping the subscription has no effect.
+pub fn never<T: Clone, E: Clone>() -> Never<T, E> {
+    Never::new()
+}
+
 impl<T: Clone, E: Clone> Observable for Never<T, E> {
     type Item = T;
     type Error = E;
     type Subscription = NeverSubscription;
 
-    fn subscribe<O>(&mut self, _observer: O) -> Self::Subscription
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<T: Clone, E: Clone> ImmutableObservable for Never<T, E> {
+    fn subscribe_ref<O>(&self, _observer: O) -> Self::Subscription
         where O: Observer<Self::Item, Self::Error> {
         // Do nothing, forget about the observer.
         NeverSubscription
     }
 }
 
-impl Drop for NeverSubscription {
-    fn drop(&mut self) {
-        // This is a no-op.
+/// An observable that pushes the same value a number of times.
+pub struct RepeatObservable<T: Clone> {
+    value: T,
+    count: usize,
+}
+
+/// Creates an observable that pushes `value` `count` times and then completes.
+///
+/// The values are pushed upon subscription, the observable completes before
+/// the call to `subscribe()` returns. This observable does not fail.
+pub fn repeat<T: Clone>(value: T, count: usize) -> RepeatObservable<T> {
+    RepeatObservable {
+        value: value,
+        count: count,
+    }
+}
+
+impl<T: Clone> Observable for RepeatObservable<T> {
+    type Item = T;
+    type Error = ();
+    type Subscription = UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<T: Clone> ImmutableObservable for RepeatObservable<T> {
+    fn subscribe_ref<O>(&self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        for _ in 0..self.count {
+            observer.on_next(self.value.clone());
+        }
+        observer.on_completed();
+        UncancellableSubscription
+    }
+}
+
+/// An observable that pushes the values of a range, see `range()`.
+pub struct RangeObservable<T> {
+    range: Range<T>,
+}
+
+/// Creates an observable that pushes the values from `start` up to `end`.
+///
+/// Upon subscription, this pushes every value of the half-open range
+/// `start..end` in order, and then completes. The observable can be subscribed
+/// to again, it pushes the same values every time. The returned subscription
+/// is not cancellable: the observable completes before the call to
+/// `subscribe()` returns. This observable does not fail.
+///
+/// Ranges themselves do not implement `Observable`, because then the
+/// observable methods would conflict with the iterator methods of a range.
+pub fn range<T>(start: T, end: T) -> RangeObservable<T>
+    where T: Clone, Range<T>: Iterator<Item = T> {
+    RangeObservable {
+        range: start..end,
+    }
+}
+
+impl<T> Observable for RangeObservable<T>
+where T: Clone, Range<T>: Iterator<Item = T> {
+    type Item = T;
+    type Error = ();
+    type Subscription = UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<T> ImmutableObservable for RangeObservable<T>
+where T: Clone, Range<T>: Iterator<Item = T> {
+    fn subscribe_ref<O>(&self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        for x in self.range.clone() {
+            observer.on_next(x);
+        }
+        observer.on_completed();
+        UncancellableSubscription
+    }
+}
+
+/// An observable that pushes the elements of a vector, see `from_vec()`.
+pub struct FromVecObservable<T> {
+    values: Vec<T>,
+}
+
+/// Creates an observable that pushes the elements of a vector.
+///
+/// Upon subscription, this pushes a clone of every element in order, and then
+/// completes. Unlike a borrowed slice, this observable owns the values and
+/// pushes values rather than references. The returned subscription is not
+/// cancellable: the observable completes before the call to `subscribe()`
+/// returns. This observable does not fail.
+///
+/// Vectors themselves do not implement `Observable`, because then methods such
+/// as `first()` or `contains()` would resolve to the observable methods rather
+/// than to the slice methods.
+pub fn from_vec<T: Clone>(values: Vec<T>) -> FromVecObservable<T> {
+    FromVecObservable {
+        values: values,
+    }
+}
+
+impl<T: Clone> Observable for FromVecObservable<T> {
+    type Item = T;
+    type Error = ();
+    type Subscription = UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<T: Clone> ImmutableObservable for FromVecObservable<T> {
+    fn subscribe_ref<O>(&self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        for x in self.values.iter() {
+            observer.on_next(x.clone());
+        }
+        observer.on_completed();
+        UncancellableSubscription
+    }
+}
+
+/// An observable that generates values from a state, see `unfold()`.
+pub struct UnfoldObservable<S: Clone, F> {
+    seed: S,
+    step: F,
+}
+
+/// Creates an observable that generates values by repeatedly applying `step`.
+///
+/// Upon subscription, `step` is called with the seed. If it returns
+/// `Some((value, state))`, the value is pushed, and `step` is called again with
+/// the new state. When it returns `None`, the observable completes. Every
+/// subscription starts again from the seed. All values are pushed before the
+/// call to `subscribe()` returns, so if `step` never returns `None`, the call
+/// never returns. This observable does not fail.
+pub fn unfold<S, T, F>(seed: S, step: F) -> UnfoldObservable<S, F>
+    where S: Clone, T: Clone, F: Fn(S) -> Option<(T, S)> {
+    UnfoldObservable {
+        seed: seed,
+        step: step,
+    }
+}
+
+impl<S, T, F> Observable for UnfoldObservable<S, F>
+where S: Clone,
+      T: Clone,
+      F: Fn(S) -> Option<(T, S)> {
+    type Item = T;
+    type Error = ();
+    type Subscription = UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<S, T, F> ImmutableObservable for UnfoldObservable<S, F>
+where S: Clone,
+      T: Clone,
+      F: Fn(S) -> Option<(T, S)> {
+    fn subscribe_ref<O>(&self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let mut state = self.seed.clone();
+        while let Some((value, next_state)) = self.step.call((state,)) {
+            observer.on_next(value);
+            state = next_state;
+        }
+        observer.on_completed();
+        UncancellableSubscription
+    }
+}
+
+/// An observable that pulls values from a function, see `from_fn()`.
+pub struct FromFnObservable<F> {
+    f: F,
+}
+
+/// Creates an observable that pushes values returned by `f` until it returns `None`.
+///
+/// Upon subscription, `f` is called repeatedly, and for every `Some(value)`
+/// that it returns, the value is pushed. When it returns `None`, the
+/// observable completes. All values are pushed before the call to
+/// `subscribe()` returns. Unlike with `unfold()`, there is no seed to start
+/// from, so a second subscription continues with whatever `f` returns next.
+/// This observable does not fail.
+pub fn from_fn<T, F>(f: F) -> FromFnObservable<F>
+    where T: Clone, F: FnMut() -> Option<T> {
+    FromFnObservable {
+        f: f,
+    }
+}
+
+impl<T, F> Observable for FromFnObservable<F>
+where T: Clone,
+      F: FnMut() -> Option<T> {
+    type Item = T;
+    type Error = ();
+    type Subscription = UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        while let Some(value) = self.f.call_mut(()) {
+            observer.on_next(value);
+        }
+        observer.on_completed();
+        UncancellableSubscription
+    }
+}
+
+/// An observable that pushes the entries of a map, see `from_map()`.
+pub struct MapEntriesObservable<'m, M: 'm + ?Sized> {
+    map: &'m M,
+}
+
+/// Creates an observable that pushes the entries of a map as key-value pairs.
+///
+/// This works for any map that can be iterated by reference, such as a
+/// `HashMap` or a `BTreeMap`. Upon subscription, a clone of every key-value
+/// pair is pushed, and then the observable completes. The entries are pushed
+/// in iteration order of the map: sorted by key for a `BTreeMap`, and
+/// unspecified for a `HashMap`. This observable does not fail.
+pub fn from_map<'m, M: ?Sized, K, V>(map: &'m M) -> MapEntriesObservable<'m, M>
+    where &'m M: IntoIterator<Item = (&'m K, &'m V)>, K: 'm + Clone, V: 'm + Clone {
+    MapEntriesObservable {
+        map: map,
+    }
+}
+
+impl<'m, M: ?Sized, K, V> Observable for MapEntriesObservable<'m, M>
+where &'m M: IntoIterator<Item = (&'m K, &'m V)>,
+      K: 'm + Clone,
+      V: 'm + Clone {
+    type Item = (K, V);
+    type Error = ();
+    type Subscription = UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<'m, M: ?Sized, K, V> ImmutableObservable for MapEntriesObservable<'m, M>
+where &'m M: IntoIterator<Item = (&'m K, &'m V)>,
+      K: 'm + Clone,
+      V: 'm + Clone {
+    fn subscribe_ref<O>(&self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        for (key, value) in self.map {
+            observer.on_next((key.clone(), value.clone()));
+        }
+        observer.on_completed();
+        UncancellableSubscription
+    }
+}
+
+/// An observable that is constructed upon subscription, see `defer()`.
+pub struct DeferObservable<F> {
+    factory: F,
+}
+
+/// Creates an observable that calls `factory` for every subscription.
+///
+/// Upon subscription, `factory` is called to construct a new observable, and
+/// the observer is subscribed to that observable. The subscription is the
+/// subscription to the new observable.
+pub fn defer<Ob, F>(factory: F) -> DeferObservable<F>
+    where Ob: Observable, F: FnMut() -> Ob {
+    DeferObservable {
+        factory: factory,
+    }
+}
+
+impl<Ob, F> Observable for DeferObservable<F>
+where Ob: Observable,
+      F: FnMut() -> Ob {
+    type Item = <Ob as Observable>::Item;
+    type Error = <Ob as Observable>::Error;
+    type Subscription = <Ob as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let mut observable = self.factory.call_mut(());
+        observable.subscribe(observer)
     }
 }
@@ -41,8 +41,9 @@
 //!
 //! TODO: Add `continue_with` example once that has been implemented.
 //!
-//! TODO: Could I have an `ImmutableObservable` to get rid of the mutability
-//! requirement?
+//! Because these observables do not need to be modified, they also implement
+//! `ImmutableObservable`, which allows subscribing through a shared reference
+//! with `subscribe_ref()`.
 //!
 //! Anything that can be converted into an iterator is also an observable. Such
 //! an observable pushes all its values immediately upon subscription. If the
@@ -60,24 +61,28 @@
 
 use std::iter::IntoIterator;
 
+mod boxed;
 mod generate;
 mod lifeline;
 mod observable;
 mod observer;
 mod subject;
+mod subscription;
 mod transform;
 
-pub use generate::Never;
-pub use observable::Observable;
-pub use observer::Observer;
-pub use subject::Subject;
+pub use boxed::BoxObservable;
+pub use generate::{Never, defer, from_fn, from_map, from_vec, never, range, repeat, unfold};
+pub use observable::{ImmutableObservable, Observable};
+pub use observer::{BoxedObserver, ChannelObserver, MapObserver, Notification, Observer};
+pub use observer::map_observer;
+pub use subject::{BehaviorSubject, Subject, SubjectHandle, SubjectSink};
+pub use subscription::{OnUnsubscribe, Subscription};
+pub use transform::{SingleError, concat, merge_all, merge_all_delay_error, zip_all};
 
 /// A subscription where `drop()` is a no-op.
 pub struct UncancellableSubscription;
 
-impl Drop for UncancellableSubscription {
-    fn drop(&mut self) { }
-}
+impl Subscription for UncancellableSubscription { }
 
 /// Observable implementation for types that can be converted into an iterator.
 ///
@@ -85,12 +90,19 @@ impl Drop for UncancellableSubscription {
 /// iterator and then completes (if the iterator is finite). The returned
 /// subscription is not cancellable: if the observable completes, it completes
 /// before the call to `subscribe()` returns. This observable does not fail.
-impl<'i, I> Observable for &'i I where &'i I: IntoIterator, <&'i I as IntoIterator>::Item: Clone {
+impl<'i, I: ?Sized> Observable for &'i I where &'i I: IntoIterator, <&'i I as IntoIterator>::Item: Clone {
     type Item = <&'i I as IntoIterator>::Item;
     type Error = ();
     type Subscription = UncancellableSubscription;
 
-    fn subscribe<O>(&mut self, mut observer: O) -> UncancellableSubscription
+    fn subscribe<O>(&mut self, observer: O) -> UncancellableSubscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<'i, I: ?Sized> ImmutableObservable for &'i I where &'i I: IntoIterator, <&'i I as IntoIterator>::Item: Clone {
+    fn subscribe_ref<O>(&self, mut observer: O) -> UncancellableSubscription
         where O: Observer<Self::Item, Self::Error> {
         for x in self.into_iter() {
             observer.on_next(x);
@@ -111,7 +123,14 @@ impl<T: Clone, E: Clone> Observable for Result<T, E> {
     type Error = E;
     type Subscription = UncancellableSubscription;
 
-    fn subscribe<O>(&mut self, mut observer: O) -> UncancellableSubscription
+    fn subscribe<O>(&mut self, observer: O) -> UncancellableSubscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<T: Clone, E: Clone> ImmutableObservable for Result<T, E> {
+    fn subscribe_ref<O>(&self, mut observer: O) -> UncancellableSubscription
         where O: Observer<Self::Item, Self::Error> {
         match *self {
             Ok(ref item) => {
@@ -137,7 +156,14 @@ impl<T: Clone> Observable for Option<T> {
     type Error = ();
     type Subscription = UncancellableSubscription;
 
-    fn subscribe<O>(&mut self, mut observer: O) -> UncancellableSubscription
+    fn subscribe<O>(&mut self, observer: O) -> UncancellableSubscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subscribe_ref(observer)
+    }
+}
+
+impl<T: Clone> ImmutableObservable for Option<T> {
+    fn subscribe_ref<O>(&self, mut observer: O) -> UncancellableSubscription
         where O: Observer<Self::Item, Self::Error> {
         if let Some(ref item) = *self {
             observer.on_next(item.clone());
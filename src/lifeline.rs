@@ -18,16 +18,48 @@ use std::rc::{Rc, Weak};
 
 /// Struct that controls the lifetime of the value in the lifeline-owner pair.
 pub struct Lifeline<T> {
-    #[allow(dead_code)] // This code is not dead, the Rc keeps the value alive.
     value: Rc<RefCell<Option<T>>>,
 }
 
-
 /// Struct that allows access to the value in the lifeline-owner pair.
 pub struct Owner<T> {
     value: Weak<RefCell<Option<T>>>,
 }
 
+impl<T> Lifeline<T> {
+    /// Performs the action on the stored value if the owner did not take it.
+    pub fn with_mut_value<F: FnOnce(&mut T)>(&mut self, action: F) {
+        if let Some(ref mut value) = *self.value.borrow_mut() {
+            action(value);
+        }
+    }
+
+    /// Returns a new owner with access to the stored value.
+    ///
+    /// All owners of a lifeline share the same value. They can be used in
+    /// turn, but like with a `RefCell`, the value must not be accessed
+    /// through one owner while it is being accessed through another.
+    pub fn owner(&self) -> Owner<T> {
+        Owner { value: Rc::downgrade(&self.value) }
+    }
+
+    /// Drops the stored value, as if the lifeline was dropped.
+    ///
+    /// Owners see a dead value afterwards. This is a no-op if an owner took
+    /// the value already.
+    pub fn cancel(&self) {
+        // The value must not be borrowed while it is dropped, in case dropping
+        // it ends up accessing the value through an owner.
+        let value = self.value.borrow_mut().take();
+        drop(value);
+    }
+
+    /// Returns whether the stored value has been dropped or taken by an owner.
+    pub fn is_expired(&self) -> bool {
+        self.value.borrow().is_none()
+    }
+}
+
 impl<T> Owner<T> {
     /// Performs the action on the stored value if it is still alive.
     pub fn with_mut_value<F: FnOnce(&mut T)>(&mut self, action: F) {
@@ -40,16 +72,45 @@ impl<T> Owner<T> {
 
     /// Performs the action on the stored value if it is still alive,
     /// calls `on_dead` otherwise.
-    pub fn with_mut_value_or<F: FnOnce(&mut T), G: FnOnce()>(&mut self,
-                                                             on_alive: F,
-                                                             on_dead: G) {
+    ///
+    /// The value is dead if the lifeline was dropped, or if an owner took the
+    /// value out. Returns the result of whichever function was called.
+    pub fn with_mut_value_or<R, F: FnOnce(&mut T) -> R, G: FnOnce() -> R>(&mut self,
+                                                                        on_alive: F,
+                                                                        on_dead: G)
+                                                                        -> R {
         if let Some(cell) = self.value.upgrade() {
             if let Some(ref mut value) = *cell.borrow_mut() {
-                on_alive(value);
-                return;
+                return on_alive(value);
             }
         }
-        on_dead();
+        on_dead()
+    }
+
+    /// Performs the action on the stored value if it is still alive,
+    /// calls `on_dead` otherwise.
+    ///
+    /// Unlike `with_mut_value_or()`, this does not need a mutable owner.
+    pub fn with_value_or_else<R, F: FnOnce(&T) -> R, G: FnOnce() -> R>(&self,
+                                                                     on_alive: F,
+                                                                     on_dead: G)
+                                                                     -> R {
+        if let Some(cell) = self.value.upgrade() {
+            if let Some(ref value) = *cell.borrow() {
+                return on_alive(value);
+            }
+        }
+        on_dead()
+    }
+
+    /// Returns whether the stored value is still alive.
+    ///
+    /// A value that is being accessed through another owner counts as alive.
+    pub fn is_alive(&self) -> bool {
+        match self.value.upgrade() {
+            Some(cell) => cell.try_borrow().map(|value| value.is_some()).unwrap_or(true),
+            None => false,
+        }
     }
 
     /// Returns the stored value if it is still alive.
@@ -62,6 +123,13 @@ impl<T> Owner<T> {
     }
 }
 
+impl<T> Clone for Owner<T> {
+    /// Returns a new owner with access to the same value.
+    fn clone(&self) -> Owner<T> {
+        Owner { value: self.value.clone() }
+    }
+}
+
 /// Creates a value with decoupled lifetime and ownership.
 pub fn new<T>(value: T) -> (Lifeline<T>, Owner<T>) {
     let rc = Rc::new(RefCell::new(Some(value)));
@@ -69,3 +137,94 @@ pub fn new<T>(value: T) -> (Lifeline<T>, Owner<T>) {
     let lifeline = Lifeline { value: rc };
     (lifeline, owner)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::new;
+
+    #[test]
+    fn cancel_expires_lifeline() {
+        let (lifeline, _owner) = new(2u8);
+        assert!(!lifeline.is_expired());
+        lifeline.cancel();
+        assert!(lifeline.is_expired());
+    }
+
+    #[test]
+    fn cancel_kills_owner_value() {
+        let (lifeline, mut owner) = new(2u8);
+        lifeline.cancel();
+        assert_eq!(None, owner.with_mut_value_or(|value| Some(*value), || None));
+    }
+
+    #[test]
+    fn owner_is_alive() {
+        let (lifeline, owner) = new(2u8);
+        assert!(owner.is_alive());
+        drop(lifeline);
+        assert!(!owner.is_alive());
+    }
+
+    #[test]
+    fn with_mut_value_or_alive() {
+        let (_lifeline, mut owner) = new(2u8);
+        owner.with_mut_value_or(|value| *value = 3, || panic!("value should be alive"));
+        assert_eq!(Some(3), owner.with_mut_value_or(|value| Some(*value), || None));
+    }
+
+    #[test]
+    fn with_mut_value_or_lifeline_dropped() {
+        let (lifeline, mut owner) = new(2u8);
+        drop(lifeline);
+        assert_eq!(None, owner.with_mut_value_or(|value| Some(*value), || None));
+    }
+
+    #[test]
+    fn with_mut_value_or_taken() {
+        let (lifeline, owner) = new(2u8);
+        let mut other = lifeline.owner();
+        assert_eq!(Some(2), owner.take());
+        assert_eq!(None, other.with_mut_value_or(|value| Some(*value), || None));
+    }
+
+    #[test]
+    fn with_value_or_else_alive() {
+        let (_lifeline, owner) = new(2u8);
+        assert_eq!(Some(2), owner.with_value_or_else(|value| Some(*value), || None));
+    }
+
+    #[test]
+    fn with_value_or_else_lifeline_dropped() {
+        let (lifeline, owner) = new(2u8);
+        drop(lifeline);
+        assert_eq!(None, owner.with_value_or_else(|value| Some(*value), || None));
+    }
+
+    #[test]
+    fn with_value_or_else_taken() {
+        let (lifeline, owner) = new(2u8);
+        let other = lifeline.owner();
+        assert_eq!(Some(2), owner.take());
+        assert_eq!(None, other.with_value_or_else(|value| Some(*value), || None));
+    }
+
+    #[test]
+    fn cloned_owner_shares_value() {
+        let (lifeline, mut owner) = new(2u8);
+        let mut cloned = owner.clone();
+        owner.with_mut_value(|value| *value = 3);
+        assert_eq!(Some(3), cloned.with_mut_value_or(|value| Some(*value), || None));
+        drop(lifeline);
+        assert!(!owner.is_alive());
+        assert!(!cloned.is_alive());
+    }
+
+    #[test]
+    fn cloned_owner_sees_take() {
+        let (_lifeline, owner) = new(2u8);
+        let cloned = owner.clone();
+        assert_eq!(Some(2), cloned.take());
+        assert!(!owner.is_alive());
+        assert_eq!(None, owner.take());
+    }
+}
@@ -5,10 +5,38 @@
 // you may not use this file except in compliance with the License.
 // A copy of the License has been included in the root of the repository.
 
-use observer::Observer;
+use boxed::BoxObservable;
+use observer::{BoxedObserver, ChannelObserver, Notification, Observer};
 use observer::{NextObserver, CompletedObserver, ErrorObserver, OptionObserver, ResultObserver};
 use std::fmt::Debug;
-use transform::{ContinueWithObservable, MapErrorObservable, MapObservable};
+use std::sync::mpsc::Sender;
+use std::vec;
+use std::hash::Hash;
+use std::ops::Add;
+use subject::Subject;
+use subscription::Subscription;
+use transform;
+use transform::{AllObservable, AmbObservable, AnyObservable, BufferBoundaryObservable};
+use transform::{BufferCountObservable, CacheObservable, CatchObservable, ChainObservable};
+use transform::{CheckedObservable, CombineLatestObservable, CombineLatestWithObservable};
+use transform::{ConcatMapObservable, ConnectableObservable, ContainsObservable};
+use transform::{ContinueWithObservable, DelayByCountObservable, DematerializeObservable};
+use transform::{DistinctByObservable, DistinctObservable, ElementAtObservable, EnumerateObservable};
+use transform::{FilterMapObservable, FilterObservable, FinallyObservable, FirstObservable};
+use transform::{FlatMapLimitObservable, FlatMapObservable, FlattenObservable, FoldObservable};
+use transform::{GroupByObservable, IndexIntervalByObservable, IndexIntervalObservable};
+use transform::{InspectCompletedObservable, InspectErrorObservable, InspectObservable};
+use transform::{InstrumentObservable, LastObservable, MapErrorObservable, MapObservable};
+use transform::{MapToObservable, MaterializeObservable, MaxObservable, MergeObservable};
+use transform::{MinObservable, OnErrorCompleteObservable, OnErrorReturnObservable};
+use transform::{OnErrorReturnWithObservable, OnUnsubscribeObservable, PausableObservable};
+use transform::{ReduceObservable, RepeatSequenceObservable, RepeatWhenObservable, RetryObservable};
+use transform::{RetryWhenObservable, SampleEveryObservable, SampleFirstOfObservable};
+use transform::{ScanObservable, SequenceEqualObservable, SharedObservable, SingleError};
+use transform::{SingleObservable, SkipLastObservable, SkipObservable, SkipUntilObservable};
+use transform::{StartWithObservable, StartWithObservableObservable, SumObservable};
+use transform::{TakeUntilObservable, TapObservable, ToVecObservable, TryMapObservable};
+use transform::{WindowCountObservable, WithLatestFromObservable, ZipObservable, ZipWithObservable};
 
 /// A stream of values.
 ///
@@ -43,8 +71,11 @@ pub trait Observable {
     type Error: Clone;
 
     /// The result of subscribing an observer.
-    // TODO: This drop bound is not required and it only complicates stuff, remove it.
-    type Subscription: Drop;
+    ///
+    /// Dropping the subscription (or calling `unsubscribe()` on it) should
+    /// stop the observable from calling the observer. Observables that cannot
+    /// be cancelled can use `()`.
+    type Subscription: Subscription;
 
     /// Subscribes an observer and returns the subscription.
     ///
@@ -170,18 +201,672 @@ pub trait Observable {
         self.subscribe(observer)
     }
 
+    /// Subscribes and collects the values produced into a vector.
+    ///
+    /// The subscription is dropped before this returns, so this is only
+    /// meaningful for observables that push all their values and complete
+    /// during the call to `subscribe()`, such as slices. Values that an
+    /// observable would push after that are not collected.
+    ///
+    /// **This panics if the observable fails with an error.**
+    ///
+    /// See also [`to_vec()`](#method.to_vec).
+    fn subscribe_collect(&mut self) -> Vec<Self::Item>
+        where Self::Error: Debug {
+        let mut values = Vec::new();
+        {
+            let _subscription = self.subscribe_next(|x| values.push(x));
+        }
+        values
+    }
+
+    /// Subscribes and returns an iterator over the values produced.
+    ///
+    /// This collects the values like `subscribe_collect()`, so it only works
+    /// for observables that push all their values during the call to
+    /// `subscribe()`. There is no way to block until an observable pushes a
+    /// value later.
+    ///
+    /// **This panics if the observable fails with an error.**
+    fn collect_iter(&mut self) -> vec::IntoIter<Self::Item>
+        where Self::Error: Debug {
+        self.subscribe_collect().into_iter()
+    }
+
+    /// Subscribes a function and returns the number of values it received.
+    ///
+    /// As with `subscribe_collect()`, the subscription is dropped before this
+    /// returns, so the count is only meaningful for observables that push all
+    /// their values during the call to `subscribe()`, such as slices.
+    ///
+    /// **This panics if the observable fails with an error.**
+    ///
+    /// See also [`subscribe_next()`](#method.subscribe_next).
+    fn run_count_next<FnNext>(&mut self, mut on_next: FnNext) -> usize
+        where Self::Error: Debug, FnNext: FnMut(Self::Item) {
+        let mut count = 0;
+        {
+            let _subscription = self.subscribe_next(|x| {
+                count += 1;
+                on_next(x);
+            });
+        }
+        count
+    }
+
+    /// Subscribes a subject, so it pushes the values to its own observers.
+    ///
+    /// Completion and failure are passed on through `Subject::complete()` and
+    /// `Subject::fail()`, so the subject is not consumed.
+    ///
+    /// See also [`subscribe()`](#tymethod.subscribe).
+    fn pipe_into<'s>(&mut self, subject: &'s mut Subject<Self::Item, Self::Error>) -> Self::Subscription {
+        self.subscribe(subject)
+    }
+
+    /// Subscribes a channel that receives the values.
+    ///
+    /// Every value produced by the observable is sent into the channel. When
+    /// the observable completes or fails, the sender is dropped, so the
+    /// receiver sees the channel close once all other senders are gone too.
+    /// The error itself is discarded. If the receiver is dropped, values are
+    /// silently discarded.
+    ///
+    /// See also [`subscribe()`](#tymethod.subscribe).
+    fn subscribe_sender(&mut self, sender: Sender<Self::Item>) -> Self::Subscription {
+        self.subscribe(ChannelObserver::new(sender))
+    }
+
+    /// Subscribes an observer of which the concrete type has been erased.
+    ///
+    /// This makes it possible to choose the observer at runtime. The box is
+    /// itself an observer that delegates to the boxed observer.
+    ///
+    /// See also [`subscribe()`](#tymethod.subscribe).
+    fn subscribe_boxed<'o>(&mut self, observer: Box<BoxedObserver<Self::Item, Self::Error> + 'o>)
+                           -> Self::Subscription {
+        self.subscribe(observer)
+    }
+
     /// Transforms an observable by applying f to every value produced.
     fn map<'s, U, F>(&'s mut self, f: F) -> MapObservable<'s, Self, F>
         where F: Fn(Self::Item) -> U {
         MapObservable::new(self, f)
     }
 
+    /// Replaces every value produced with a clone of `value`.
+    ///
+    /// This is useful when only the occurrence of values matters, not the
+    /// values themselves. Completion and failure are passed through unchanged.
+    fn map_to<'s, U>(&'s mut self, value: U) -> MapToObservable<'s, Self, U>
+        where U: Clone {
+        MapToObservable::new(self, value)
+    }
+
+    /// Transforms values with a function that may fail.
+    ///
+    /// For every value produced, f is called. If it returns `Ok(x)`, then `x`
+    /// is pushed. If it returns `Err(error)`, the observer fails with that
+    /// error, and the subscription to the observable is dropped.
+    fn try_map<'s, U, F>(&'s mut self, f: F) -> TryMapObservable<'s, Self, F>
+        where F: Fn(Self::Item) -> Result<U, Self::Error>, U: Clone {
+        TryMapObservable::new(self, f)
+    }
+
+    /// Pairs every value with its index.
+    ///
+    /// The index of the first value is 0, and it increases by one for every
+    /// value. Completion and failure are passed through unchanged. This is the
+    /// observable counterpart of `Iterator::enumerate()`.
+    fn enumerate<'s>(&'s mut self) -> EnumerateObservable<'s, Self> {
+        EnumerateObservable::new(self)
+    }
+
+    /// Pairs every value with the distance to the previous value.
+    ///
+    /// This is a clock-free counterpart of a time interval: the distance is
+    /// measured in values, so it is 0 for the first value and 1 for every
+    /// value after that. To measure gaps in a sequence, use
+    /// [`index_interval_by()`](#method.index_interval_by).
+    fn index_interval<'s>(&'s mut self) -> IndexIntervalObservable<'s, Self> {
+        IndexIntervalByObservable::new(self, transform::arrival_index)
+    }
+
+    /// Pairs every value with the distance between its position and that of
+    /// the previous value.
+    ///
+    /// The function receives the index at which the value arrived, and the
+    /// value itself, and returns the position of the value. For instance, it
+    /// can extract a sequence number from the value, so the distance reveals
+    /// how many values are missing in between. The distance is 0 for the first
+    /// value. Positions are expected to increase; a position lower than the
+    /// previous one yields a distance of 0. Completion and failure are passed
+    /// through unchanged.
+    fn index_interval_by<'s, F>(&'s mut self, position: F) -> IndexIntervalByObservable<'s, Self, F>
+        where F: Fn(usize, &Self::Item) -> usize {
+        IndexIntervalByObservable::new(self, position)
+    }
+
+    /// Drops the values for which the predicate returns false.
+    ///
+    /// The predicate receives every value by reference. Values for which it
+    /// returns true are pushed unchanged, the others are discarded.
+    fn filter<'s, P>(&'s mut self, predicate: P) -> FilterObservable<'s, Self, P>
+        where P: Fn(&Self::Item) -> bool {
+        FilterObservable::new(self, predicate)
+    }
+
+    /// Transforms values with a function that may discard them.
+    ///
+    /// For every value produced, f is called. If it returns `Some(x)`, then
+    /// `x` is pushed, if it returns `None`, nothing is pushed. This is the
+    /// observable counterpart of `Iterator::filter_map()`.
+    fn filter_map<'s, U, F>(&'s mut self, f: F) -> FilterMapObservable<'s, Self, F>
+        where F: Fn(Self::Item) -> Option<U>, U: Clone {
+        FilterMapObservable::new(self, f)
+    }
+
     /// Transforms an observable by applying f the error in case of failure.
     fn map_error<'s, F, G>(&'s mut self, f: G) -> MapErrorObservable<'s, Self, G>
         where G: Fn(Self::Error) -> F {
         MapErrorObservable::new(self, f)
     }
 
+    /// Drops the first `n` values and pushes the remaining ones.
+    ///
+    /// Completion and failure are passed through unchanged, also when the
+    /// observable produced fewer than `n` values.
+    fn skip<'s>(&'s mut self, n: usize) -> SkipObservable<'s, Self> {
+        SkipObservable::new(self, n)
+    }
+
+    /// Drops the last `n` values and pushes the others.
+    ///
+    /// Because it is not known which values are the last ones until the
+    /// observable completes, the `n` most recent values are buffered, and a
+    /// value is pushed only after `n` more values have been produced.
+    /// Completion and failure are passed through unchanged.
+    fn skip_last<'s>(&'s mut self, n: usize) -> SkipLastObservable<'s, Self> {
+        SkipLastObservable::new(self, n)
+    }
+
+    /// Delays every value until `n` more values have been produced.
+    ///
+    /// The first value is pushed when the value at index `n` is produced, the
+    /// second one when the value at index `n + 1` is produced, and so on. When
+    /// the observable completes, the values that are still delayed are pushed
+    /// before completing. When it fails, they are discarded and the error is
+    /// passed through immediately. With `n` zero, this has no effect.
+    fn delay_by_count<'s>(&'s mut self, n: usize) -> DelayByCountObservable<'s, Self> {
+        DelayByCountObservable::new(self, n)
+    }
+
+    /// Drops values that have been pushed before.
+    ///
+    /// Every value that is not equal to a value produced earlier is pushed,
+    /// in the order in which it was produced. To do so, a copy of every
+    /// distinct value is kept for the lifetime of the subscription, so for an
+    /// infinite observable the memory usage can grow without bound.
+    fn distinct<'s>(&'s mut self) -> DistinctObservable<'s, Self>
+        where Self::Item: Eq + Hash {
+        DistinctByObservable::new(self, Clone::clone)
+    }
+
+    /// Drops values whose key is equal to that of a value pushed before.
+    ///
+    /// This is like [`distinct()`](#method.distinct), but values are compared
+    /// by the key that the function returns for them, so the values themselves
+    /// need not be hashable. Of values with equal keys, the first one is
+    /// pushed. All distinct keys are kept, so the memory usage can grow
+    /// without bound for an infinite observable.
+    fn distinct_by<'s, K, F>(&'s mut self, key: F) -> DistinctByObservable<'s, Self, F>
+        where K: Eq + Hash, F: Fn(&Self::Item) -> K {
+        DistinctByObservable::new(self, key)
+    }
+
+    /// Pushes only every `n`-th value, starting with the first one.
+    ///
+    /// The first value is pushed, then `n - 1` values are dropped, then the
+    /// next one is pushed, and so on. This is a deterministic alternative to
+    /// time-based throttling. Completion and failure are passed through
+    /// unchanged; values dropped before completion are not pushed. Panics if
+    /// `n` is zero.
+    fn sample_every<'s>(&'s mut self, n: usize) -> SampleEveryObservable<'s, Self> {
+        SampleEveryObservable::new(self, n)
+    }
+
+    /// Pushes the first value of every group of `n` consecutive values.
+    ///
+    /// This collapses bursts of values without a timer: of every `n` values,
+    /// the first is pushed and the other `n - 1` are dropped. It is the same
+    /// as `sample_every()`, under the name that describes it from the point of
+    /// view of the groups. Panics if `n` is zero.
+    fn sample_first_of<'s>(&'s mut self, n: usize) -> SampleFirstOfObservable<'s, Self> {
+        SampleEveryObservable::new(self, n)
+    }
+
+    /// Pushes the running accumulation of the values produced.
+    ///
+    /// For every value, the accumulator is updated to `f(accumulator, value)`
+    /// and the new accumulator is pushed. The accumulator starts out as
+    /// `initial`, which is not pushed itself: an observable that produces no
+    /// values results in an observable that produces no values. Use
+    /// `scan_seeded()` to push the initial accumulator too.
+    fn scan<'s, A, F>(&'s mut self, initial: A, f: F) -> ScanObservable<'s, Self, A, F>
+        where A: Clone, F: Fn(A, Self::Item) -> A {
+        ScanObservable::new(self, initial, f)
+    }
+
+    /// Pushes the initial accumulator, and then the running accumulation.
+    ///
+    /// This is like `scan()`, but `initial` is pushed upon subscription,
+    /// before any value is produced. The observer therefore receives one
+    /// accumulator more than with `scan()`, and it receives `initial` even if
+    /// the observable produces no values.
+    fn scan_seeded<'s, A, F>(&'s mut self, initial: A, f: F) -> ScanObservable<'s, Self, A, F>
+        where A: Clone, F: Fn(A, Self::Item) -> A {
+        ScanObservable::new_seeded(self, initial, f)
+    }
+
+    /// Aggregates all values produced into a single value.
+    ///
+    /// The accumulator starts out as `initial`, and for every value it is
+    /// updated to `f(accumulator, value)`. Nothing is pushed until the
+    /// observable completes, then the final accumulator is pushed, followed by
+    /// completion. If the observable fails, the error is passed through and
+    /// the accumulator is discarded. See also [`scan()`](#method.scan).
+    fn fold<'s, A, F>(&'s mut self, initial: A, f: F) -> FoldObservable<'s, Self, A, F>
+        where A: Clone, F: Fn(A, Self::Item) -> A {
+        FoldObservable::new(self, initial, f, Some)
+    }
+
+    /// Pushes the smallest value produced.
+    ///
+    /// Nothing is pushed until the observable completes, then the smallest
+    /// value is pushed, followed by completion. If the observable completes
+    /// without producing a value, nothing is pushed. If it fails, the error is
+    /// passed through. Of equal values, the first one is pushed.
+    ///
+    /// For types that implement both `Ord` and `Observable`, such as slices,
+    /// `Ord::min` shadows this method: method call syntax resolves to
+    /// `Ord::min`, so write `Observable::min(&mut x)` instead.
+    fn min<'s>(&'s mut self) -> MinObservable<'s, Self>
+        where Self::Item: PartialOrd {
+        ReduceObservable::new(self, None, transform::min_step, transform::identity)
+    }
+
+    /// Pushes the largest value produced.
+    ///
+    /// Nothing is pushed until the observable completes, then the largest
+    /// value is pushed, followed by completion. If the observable completes
+    /// without producing a value, nothing is pushed. If it fails, the error is
+    /// passed through. Of equal values, the first one is pushed.
+    ///
+    /// For types that implement both `Ord` and `Observable`, such as slices,
+    /// `Ord::max` shadows this method: method call syntax resolves to
+    /// `Ord::max`, so write `Observable::max(&mut x)` instead.
+    fn max<'s>(&'s mut self) -> MaxObservable<'s, Self>
+        where Self::Item: PartialOrd {
+        ReduceObservable::new(self, None, transform::max_step, transform::identity)
+    }
+
+    /// Pushes the sum of all values produced.
+    ///
+    /// Nothing is pushed until the observable completes, then the sum is
+    /// pushed, followed by completion. If the observable completes without
+    /// producing a value, the default value (zero for numbers) is pushed. If
+    /// it fails, the error is passed through.
+    fn sum<'s>(&'s mut self) -> SumObservable<'s, Self>
+        where Self::Item: Add<Output = Self::Item> + Default {
+        ReduceObservable::new(self, Default::default(), transform::sum_step, Some)
+    }
+
+    /// Pushes only the first value produced, and then completes.
+    ///
+    /// After the first value has been pushed, the subscription to the
+    /// observable is dropped. If the observable completes without producing a
+    /// value, this completes without pushing a value. If it fails before
+    /// producing a value, the error is passed through.
+    fn first<'s>(&'s mut self) -> FirstObservable<'s, Self> {
+        FirstObservable::new(self)
+    }
+
+    /// Pushes only the value at the given index, and then completes.
+    ///
+    /// The index is zero-based, so `element_at(0)` is equivalent to `first()`.
+    /// After the value has been pushed, the subscription to the observable is
+    /// dropped. If the observable completes before producing enough values,
+    /// this completes without pushing a value; it is not an error. If it fails
+    /// before producing the value, the error is passed through.
+    fn element_at<'s>(&'s mut self, index: usize) -> ElementAtObservable<'s, Self> {
+        ElementAtObservable::new(self, index)
+    }
+
+    /// Pushes only the last value produced.
+    ///
+    /// Nothing is pushed until the observable completes, then the most recent
+    /// value (if any) is pushed, followed by completion. If the observable
+    /// fails, the error is passed through without pushing a value.
+    fn last<'s>(&'s mut self) -> LastObservable<'s, Self> {
+        LastObservable::new(self)
+    }
+
+    /// Pushes the only value produced, and fails if there is not exactly one.
+    ///
+    /// Nothing is pushed until the observable completes. If it produced
+    /// exactly one value, that value is pushed, followed by completion. If it
+    /// completes without producing a value, this fails with
+    /// `SingleError::Empty`. As soon as it produces a second value, this fails
+    /// with `SingleError::Multiple` and the subscription to the observable is
+    /// dropped. If the observable fails, the error is passed through.
+    ///
+    /// The error type of the observable must be constructible from a
+    /// `SingleError`. It is implemented for `()`, the error type of the
+    /// observables for collections. Note that `subscribe_next()` panics when
+    /// the observable fails, so with `subscribe_next()` this panics unless
+    /// there is exactly one value.
+    fn single<'s>(&'s mut self) -> SingleObservable<'s, Self>
+        where Self::Error: From<SingleError> {
+        SingleObservable::new(self)
+    }
+
+    /// Pushes the given values before the values of the observable.
+    ///
+    /// Upon subscription, the values are pushed in order, and then the
+    /// observer is subscribed to the observable.
+    fn start_with<'s>(&'s mut self, values: Vec<Self::Item>) -> StartWithObservable<'s, Self> {
+        StartWithObservable::new(self, values)
+    }
+
+    /// Pushes the values of `head` before the values of the observable.
+    ///
+    /// This is `head.continue_with(self)` with the order of the observables
+    /// reversed: upon subscription, the observer is subscribed to `head`, and
+    /// after `head` completes, it is subscribed to this observable. If `head`
+    /// fails, the error is passed through and this observable is not
+    /// subscribed to.
+    fn start_with_observable<'s, ObHead>(&'s mut self, head: &'s mut ObHead) -> StartWithObservableObservable<'s, Self, ObHead>
+        where ObHead: Observable<Item = Self::Item, Error = Self::Error> {
+        ContinueWithObservable::new(head, self)
+    }
+
+    /// Recovers from failure by continuing with an alternative observable.
+    ///
+    /// Values are passed through until the observable fails. Then `handler`
+    /// is called with the error, and the observer is subscribed to the
+    /// observable that it returns, instead of receiving the error.
+    ///
+    /// Note that the handler cannot be `FnOnce`, because every observer
+    /// receives a reference to it.
+    fn catch_error<'s, F, ObAlt>(&'s mut self, handler: F) -> CatchObservable<'s, Self, F>
+        where F: Fn(Self::Error) -> ObAlt,
+              ObAlt: Observable<Item = Self::Item, Error = Self::Error> {
+        CatchObservable::new(self, handler)
+    }
+
+    /// Recovers from failure by pushing a fallback value and completing.
+    ///
+    /// Values are passed through until the observable fails. Then `value` is
+    /// pushed and the observer completes, instead of receiving the error.
+    fn on_error_return<'s>(&'s mut self, value: Self::Item) -> OnErrorReturnObservable<'s, Self> {
+        OnErrorReturnObservable::new(self, value)
+    }
+
+    /// Recovers from failure by pushing a value computed from the error.
+    ///
+    /// Like `on_error_return()`, but the fallback value is `f(error)`. As with
+    /// `catch_error()`, f cannot be `FnOnce`, because every observer receives
+    /// a reference to it.
+    fn on_error_return_with<'s, F>(&'s mut self, f: F) -> OnErrorReturnWithObservable<'s, Self, F>
+        where F: Fn(Self::Error) -> Self::Item {
+        OnErrorReturnWithObservable::new(self, f)
+    }
+
+    /// Turns failure into completion.
+    ///
+    /// Values and completion are passed through unchanged. When the observable
+    /// fails, the observer completes instead, and the error is discarded. The
+    /// error type is retained, even though the resulting observable never
+    /// fails.
+    fn on_error_complete<'s>(&'s mut self) -> OnErrorCompleteObservable<'s, Self> {
+        OnErrorCompleteObservable::new(self)
+    }
+
+    /// Pushes the values of the observable `count` times in succession.
+    ///
+    /// Every time the observable completes, it is subscribed to again, until
+    /// it has completed `count` times. Then the observer completes. If `count`
+    /// is zero, the observer completes immediately. If the observable fails,
+    /// the error is passed through.
+    ///
+    /// When a round completes after the call to `subscribe()` has returned,
+    /// the observable is subscribed to again from within the observer, and the
+    /// subscription to the completed round is dropped.
+    fn repeat<'s>(&'s mut self, count: usize) -> RepeatSequenceObservable<'s, Self> {
+        RepeatSequenceObservable::new(self, count)
+    }
+
+    /// Subscribes to the observable again every time the notifier pushes a value.
+    ///
+    /// When the observable completes, completion is not passed through.
+    /// Instead, the next value pushed by the notifier subscribes to the
+    /// observable again. Values pushed by the notifier while the observable has
+    /// not completed yet are ignored. When the notifier completes, the observer
+    /// completes too, as soon as the current round of the observable completed.
+    /// Errors of both the observable and the notifier are passed through.
+    fn repeat_when<'s, ObNotifier>(&'s mut self, notifier: &'s mut ObNotifier) -> RepeatWhenObservable<'s, Self, ObNotifier>
+        where ObNotifier: Observable<Error = Self::Error> {
+        RepeatWhenObservable::new(self, notifier)
+    }
+
+    /// Subscribes to the observable again when it fails.
+    ///
+    /// When the observable fails, the error is ignored and the observable is
+    /// subscribed to again, at most `max_attempts` times. If the last attempt
+    /// fails too, the error is passed through. Values pushed by a failed
+    /// attempt are passed through as well, so an observer may receive the
+    /// same value multiple times.
+    ///
+    /// When an attempt fails after the call to `subscribe()` has returned, the
+    /// observable is subscribed to again from within the observer, and the
+    /// subscription to the failed attempt is dropped.
+    fn retry<'s>(&'s mut self, max_attempts: usize) -> RetryObservable<'s, Self> {
+        RetryObservable::new(self, max_attempts)
+    }
+
+    /// Subscribes to the observable again when a notifier chosen by the error pushes a value.
+    ///
+    /// When the observable fails, `handler` is called with the error to obtain
+    /// a notifier observable. When the notifier pushes a value, the observable
+    /// is subscribed to again. If the notifier completes or fails before
+    /// pushing a value, completion or the error is passed through. Values
+    /// pushed by a failed attempt are passed through as well.
+    ///
+    /// When the notifier pushes a value after the call to `subscribe()` has
+    /// returned, the observable is subscribed to again from within the
+    /// notifier's observer, and the subscription to the failed attempt is
+    /// dropped.
+    fn retry_when<'s, ObNotifier, F>(&'s mut self, handler: F) -> RetryWhenObservable<'s, Self, F>
+        where ObNotifier: Observable<Error = Self::Error>,
+              F: FnMut(Self::Error) -> ObNotifier {
+        RetryWhenObservable::new(self, handler)
+    }
+
+    /// Calls f for every value produced, without changing the values.
+    ///
+    /// This is useful for side effects such as logging. The value is passed
+    /// to f by reference, and then pushed unchanged.
+    fn inspect<'s, F>(&'s mut self, f: F) -> InspectObservable<'s, Self, F>
+        where F: Fn(&Self::Item) {
+        InspectObservable::new(self, f)
+    }
+
+    /// Calls f when the observable completes, before passing on completion.
+    fn inspect_completed<'s, F>(&'s mut self, f: F) -> InspectCompletedObservable<'s, Self, F>
+        where F: Fn() {
+        InspectCompletedObservable::new(self, f)
+    }
+
+    /// Calls f with the error when the observable fails, before passing on the error.
+    fn inspect_error<'s, F>(&'s mut self, f: F) -> InspectErrorObservable<'s, Self, F>
+        where F: Fn(&Self::Error) {
+        InspectErrorObservable::new(self, f)
+    }
+
+    /// Checks that the observable honors the observer contract.
+    ///
+    /// The observer must not receive a value after the observable completed
+    /// or failed, and it must receive at most one of those. In debug builds,
+    /// a violation panics with a message that names the offending call. In
+    /// release builds notifications after termination are dropped instead.
+    /// Because the terminal methods consume the observer, a violation requires
+    /// unsafe code in the observable that duplicates the observer, so this is
+    /// mostly useful when implementing observables.
+    fn checked<'s>(&'s mut self) -> CheckedObservable<'s, Self> {
+        CheckedObservable::new(self)
+    }
+
+    /// Calls `on_subscribe` every time the observable is subscribed to.
+    ///
+    /// The function is called before the observer is subscribed to this
+    /// observable. This is useful to detect an observable that is subscribed
+    /// to more often than intended, for instance a cold observable that is
+    /// consumed twice.
+    fn instrument_subscribe<'s, F>(&'s mut self, on_subscribe: F) -> InstrumentObservable<'s, Self, F>
+        where F: FnMut() {
+        InstrumentObservable::new(self, on_subscribe)
+    }
+
+    /// Passes every call to the observer on to a side observer as well.
+    ///
+    /// Every value, completion, and error is first passed to a clone of
+    /// `side_observer`, and then to the observer, without changing the
+    /// observable. Every subscription uses its own clone of the side observer.
+    /// This is useful for tracing a pipeline.
+    fn tap<'s, O2>(&'s mut self, side_observer: O2) -> TapObservable<'s, Self, O2>
+        where O2: Observer<Self::Item, Self::Error> + Clone {
+        TapObservable::new(self, side_observer)
+    }
+
+    /// Calls f when a subscription ends.
+    ///
+    /// The function is called once per subscription: after the observable
+    /// completes, after it fails, or when the subscription is dropped before
+    /// the observable terminated, whichever happens first. This is useful for
+    /// releasing resources.
+    ///
+    /// Note that the function cannot be `FnOnce`, because every subscription
+    /// calls it.
+    fn finally<'s, F>(&'s mut self, f: F) -> FinallyObservable<'s, Self, F>
+        where F: Fn() {
+        FinallyObservable::new(self, f)
+    }
+
+    /// Calls f after a subscription is dropped.
+    ///
+    /// Every subscription wraps the subscription to the observable in an
+    /// `OnUnsubscribe`, which calls a clone of f once, after the wrapped
+    /// subscription has been dropped. Unlike `finally()`, the function is not
+    /// called when the observable terminates, only when the subscription is
+    /// dropped, and it works for any observable.
+    fn on_unsubscribe<'s, F>(&'s mut self, f: F) -> OnUnsubscribeObservable<'s, Self, F>
+        where F: FnOnce() + Clone {
+        OnUnsubscribeObservable::new(self, f)
+    }
+
+    /// Collects values into vectors, cut whenever `boundary` produces a value.
+    ///
+    /// Every time `boundary` produces a value, the values produced since the
+    /// previous boundary are pushed as a vector, which may be empty. When the
+    /// observable completes, the remaining values (if any) are pushed before
+    /// completion. Completion of `boundary` is ignored. If either of the
+    /// observables fails, the remaining values are discarded and the error is
+    /// passed through.
+    fn buffer_boundary<'s, ObSignal>(&'s mut self, boundary: &'s mut ObSignal) -> BufferBoundaryObservable<'s, Self, ObSignal>
+        where ObSignal: Observable<Error = Self::Error> {
+        BufferBoundaryObservable::new(self, boundary)
+    }
+
+    /// Collects values into vectors of `size` values.
+    ///
+    /// Every time `size` values have been produced, they are pushed as a
+    /// vector. When the observable completes, the remaining values (if any)
+    /// are pushed as a shorter vector before completion. If the observable
+    /// fails, the remaining values are discarded and the error is passed
+    /// through.
+    ///
+    /// Panics if `size` is zero.
+    fn buffer_count<'s>(&'s mut self, size: usize) -> BufferCountObservable<'s, Self> {
+        BufferCountObservable::new(self, size)
+    }
+
+    /// Splits the values into windows of `size` values.
+    ///
+    /// A window is itself an observable. When a value is produced and there is
+    /// no open window, a new window is pushed, and subsequent values are pushed
+    /// to that window. After `size` values the window completes, and the next
+    /// value opens a new window. When the observable completes or fails, the
+    /// open window completes or fails as well.
+    ///
+    /// Panics if `size` is zero.
+    fn window_count<'s>(&'s mut self, size: usize) -> WindowCountObservable<'s, Self> {
+        WindowCountObservable::new(self, size)
+    }
+
+    /// Splits the values into groups of values with the same key.
+    ///
+    /// A group is itself an observable. When a value is produced with a key
+    /// that has not been seen before, a new group is pushed together with the
+    /// key, and then the value is pushed to that group. Values with a key seen
+    /// before are pushed to the existing group. When the observable completes
+    /// or fails, all groups complete or fail as well, in no particular order.
+    fn group_by<'s, K, F>(&'s mut self, key: F) -> GroupByObservable<'s, Self, F>
+        where K: Clone + Eq + Hash, F: Fn(&Self::Item) -> K {
+        GroupByObservable::new(self, key)
+    }
+
+    /// Collects all values produced into a single vector.
+    ///
+    /// Nothing is pushed until the observable completes, then the vector of
+    /// all values is pushed, followed by completion. If the observable fails,
+    /// the values are discarded and the error is passed through.
+    fn to_vec<'s>(&'s mut self) -> ToVecObservable<'s, Self> {
+        ToVecObservable::new(self)
+    }
+
+    /// Determines whether all values satisfy the predicate.
+    ///
+    /// As soon as a value does not satisfy the predicate, `false` is pushed,
+    /// the observer completes, and the subscription to the observable is
+    /// dropped. If the observable completes before that, `true` is pushed,
+    /// followed by completion. If it fails, the error is passed through.
+    fn all<'s, P>(&'s mut self, predicate: P) -> AllObservable<'s, Self, P>
+        where P: Fn(&Self::Item) -> bool {
+        AllObservable::new(self, predicate)
+    }
+
+    /// Determines whether any value satisfies the predicate.
+    ///
+    /// As soon as a value satisfies the predicate, `true` is pushed, the
+    /// observer completes, and the subscription to the observable is dropped.
+    /// If the observable completes before that, `false` is pushed, followed by
+    /// completion. If it fails, the error is passed through.
+    fn any<'s, P>(&'s mut self, predicate: P) -> AnyObservable<'s, Self, P>
+        where P: Fn(&Self::Item) -> bool {
+        AnyObservable::new(self, predicate)
+    }
+
+    /// Determines whether the observable produces the given value.
+    ///
+    /// As soon as a value equal to `target` is produced, `true` is pushed, the
+    /// observer completes, and the subscription to the observable is dropped.
+    /// If the observable completes before that, `false` is pushed, followed by
+    /// completion. If it fails, the error is passed through.
+    fn contains<'s>(&'s mut self, target: Self::Item) -> ContainsObservable<'s, Self>
+        where Self::Item: PartialEq {
+        ContainsObservable::new(self, target)
+    }
+
     /// Joins two observables sequentially.
     ///
     /// After the current observable completes, an observer will start to
@@ -192,4 +877,334 @@ pub trait Observable {
         where ObNext: Observable<Item = Self::Item, Error = Self::Error> {
         ContinueWithObservable::new(self, next)
     }
+
+    /// Joins two observables sequentially, taking ownership of the second one.
+    ///
+    /// This is like `continue_with()`, but `next` is moved into the returned
+    /// observable rather than borrowed, so it does not need to outlive the
+    /// borrow of this observable. This makes it possible to chain more than
+    /// two observables in one expression, as in `a.chain(b).chain(c)`.
+    fn chain<'s, ObNext>(&'s mut self, next: ObNext) -> ChainObservable<'s, Self, ObNext>
+        where ObNext: Observable<Item = Self::Item, Error = Self::Error> {
+        ChainObservable::new(self, next)
+    }
+
+    /// Mirrors whichever of the two observables reacts first.
+    ///
+    /// Both observables are subscribed to. The first one to push a value,
+    /// complete, or fail wins: the subscription to the other one is dropped,
+    /// and from then on only the notifications of the winner are passed on.
+    fn amb<'s, ObOther>(&'s mut self, other: &'s mut ObOther) -> AmbObservable<'s, Self, ObOther>
+        where ObOther: Observable<Item = Self::Item, Error = Self::Error> {
+        AmbObservable::new(self, other)
+    }
+
+    /// Passes through values until `stop` pushes a value or completes.
+    ///
+    /// Both observables are subscribed to, `stop` first. As soon as `stop`
+    /// pushes a value or completes, the observer completes and the
+    /// subscription to this observable is dropped. If either of the
+    /// observables fails before that, the error is passed through.
+    fn take_until<'s, ObStop>(&'s mut self, stop: &'s mut ObStop) -> TakeUntilObservable<'s, Self, ObStop>
+        where ObStop: Observable<Error = Self::Error> {
+        TakeUntilObservable::new(self, stop)
+    }
+
+    /// Drops values until `gate` pushes a value, and passes through the rest.
+    ///
+    /// Both observables are subscribed to, `gate` first. Values produced
+    /// before `gate` pushes its first value are dropped, values produced after
+    /// that are passed through. The observable completes when this observable
+    /// completes, and completion of `gate` is ignored. If either of the
+    /// observables fails, the error is passed through.
+    fn skip_until<'s, ObGate>(&'s mut self, gate: &'s mut ObGate) -> SkipUntilObservable<'s, Self, ObGate>
+        where ObGate: Observable<Error = Self::Error> {
+        SkipUntilObservable::new(self, gate)
+    }
+
+    /// Joins two observables concurrently.
+    ///
+    /// An observer receives the values of both observables, in the order in
+    /// which they are produced. It completes after both observables have
+    /// completed. If either of the observables fails, the error is passed
+    /// through immediately, and nothing is pushed after that.
+    ///
+    /// The observer must not cause either of the observables to push a value
+    /// while it is handling a value.
+    fn merge<'s, ObOther>(&'s mut self, other: &'s mut ObOther) -> MergeObservable<'s, Self, ObOther>
+        where ObOther: Observable<Item = Self::Item, Error = Self::Error> {
+        MergeObservable::new(self, other)
+    }
+
+    /// Transforms every value into an observable, and merges those.
+    ///
+    /// For every value produced, f is called to obtain an inner observable,
+    /// and the observer is subscribed to it. The observer receives the values
+    /// of all inner observables in the order in which they are produced. It
+    /// completes after the observable and all inner observables completed. If
+    /// the observable or any of the inner observables fails, the error is
+    /// passed through immediately.
+    fn flat_map<'s, ObInner, F>(&'s mut self, f: F) -> FlatMapObservable<'s, Self, F>
+        where F: Fn(Self::Item) -> ObInner,
+              ObInner: Observable<Error = Self::Error> {
+        FlatMapObservable::new(self, f)
+    }
+
+    /// Transforms every value into an observable, and merges at most `max_concurrent` of them.
+    ///
+    /// Like `flat_map()`, but at most `max_concurrent` inner observables are
+    /// subscribed to at the same time. Values produced while that many inner
+    /// observables are active are queued, and when an inner observable
+    /// completes, the inner observable for the oldest queued value is
+    /// subscribed to. With a limit of 1, this is `concat_map()`. It completes
+    /// after the observable and all inner observables completed. If any of
+    /// them fails, the error is passed through immediately.
+    ///
+    /// Panics if `max_concurrent` is zero.
+    fn flat_map_limit<'s, ObInner, F>(&'s mut self, max_concurrent: usize, f: F) -> FlatMapLimitObservable<'s, Self, F>
+        where F: Fn(Self::Item) -> ObInner,
+              ObInner: Observable<Error = Self::Error> {
+        FlatMapLimitObservable::new(self, max_concurrent, f)
+    }
+
+    /// Transforms every value into an observable, and concatenates those.
+    ///
+    /// For every value produced, f is called to obtain an inner observable.
+    /// Unlike with `flat_map()`, the observer is subscribed to one inner
+    /// observable at a time: values produced while an inner observable is
+    /// active are queued, and the inner observable for the next value is
+    /// subscribed to when the previous one completes. This preserves the
+    /// order of the values. It completes after the observable and all inner
+    /// observables completed. If any of them fails, the error is passed
+    /// through immediately.
+    fn concat_map<'s, ObInner, F>(&'s mut self, f: F) -> ConcatMapObservable<'s, Self, F>
+        where F: Fn(Self::Item) -> ObInner,
+              ObInner: Observable<Error = Self::Error> {
+        FlatMapLimitObservable::new(self, 1, f)
+    }
+
+    /// Merges the inner observables of an observable of observables.
+    ///
+    /// This is `flat_map()` with the identity function: the observer is
+    /// subscribed to every inner observable as it is produced. It completes
+    /// after the observable and all inner observables completed.
+    fn flatten<'s>(&'s mut self) -> FlattenObservable<'s, Self>
+        where Self::Item: Observable<Error = Self::Error> {
+        FlatMapObservable::new(self, transform::identity)
+    }
+
+    /// Combines the values of two observables pairwise.
+    ///
+    /// The n-th value pushed is the pair of the n-th value of this observable
+    /// and the n-th value of `other`. Values are buffered until the other
+    /// observable produced its corresponding value. The zipped observable
+    /// completes when either of the observables completes and all of its
+    /// values have been paired. If either of the observables fails, the error
+    /// is passed through immediately.
+    fn zip<'s, ObOther>(&'s mut self, other: &'s mut ObOther) -> ZipObservable<'s, Self, ObOther>
+        where ObOther: Observable<Error = Self::Error> {
+        ZipObservable::new(self, other)
+    }
+
+    /// Combines the values of two observables pairwise with a function.
+    ///
+    /// The n-th value pushed is `combine(a, b)`, where `a` is the n-th value
+    /// of this observable and `b` the n-th value of `other`. This is
+    /// equivalent to `zip()` followed by `map()`, and completion and failure
+    /// behave the same as for `zip()`.
+    fn zip_with<'s, ObOther, U, F>(&'s mut self, other: &'s mut ObOther, combine: F) -> ZipWithObservable<'s, Self, ObOther, F>
+        where ObOther: Observable<Error = Self::Error>,
+              F: Fn(Self::Item, ObOther::Item) -> U,
+              U: Clone {
+        ZipWithObservable::new(self, other, combine)
+    }
+
+    /// Combines the most recent values of two observables.
+    ///
+    /// After both observables produced a value, every value produced by either
+    /// of them is pushed as a pair with the most recent value of the other one.
+    /// The combined observable completes after both observables completed. If
+    /// either of the observables fails, the error is passed through
+    /// immediately.
+    fn combine_latest<'s, ObOther>(&'s mut self, other: &'s mut ObOther) -> CombineLatestObservable<'s, Self, ObOther>
+        where ObOther: Observable<Error = Self::Error> {
+        CombineLatestObservable::new(self, other)
+    }
+
+    /// Combines the most recent values of two observables with a function.
+    ///
+    /// Instead of the pair of most recent values, `combine(a, b)` is pushed.
+    /// This is equivalent to `combine_latest()` followed by `map()`, and
+    /// completion and failure behave the same as for `combine_latest()`.
+    fn combine_latest_with<'s, ObOther, U, F>(&'s mut self, other: &'s mut ObOther, combine: F) -> CombineLatestWithObservable<'s, Self, ObOther, F>
+        where ObOther: Observable<Error = Self::Error>,
+              F: Fn(Self::Item, ObOther::Item) -> U,
+              U: Clone {
+        CombineLatestWithObservable::new(self, other, combine)
+    }
+
+    /// Passes values through only while the most recent control value is true.
+    ///
+    /// Values produced while `control` most recently produced `false` are
+    /// dropped. Before `control` produces its first value, values pass
+    /// through; use `pausable_with()` to start out paused. The observable
+    /// completes when this observable completes, and completion of `control`
+    /// is ignored. If either of the observables fails, the error is passed
+    /// through immediately.
+    fn pausable<'s, ObControl>(&'s mut self, control: &'s mut ObControl) -> PausableObservable<'s, Self, ObControl>
+        where ObControl: Observable<Item = bool, Error = Self::Error> {
+        PausableObservable::new(self, control, true)
+    }
+
+    /// As `pausable()`, but with the given state before the first control value.
+    fn pausable_with<'s, ObControl>(&'s mut self,
+                                    control: &'s mut ObControl,
+                                    initial: bool)
+                                    -> PausableObservable<'s, Self, ObControl>
+        where ObControl: Observable<Item = bool, Error = Self::Error> {
+        PausableObservable::new(self, control, initial)
+    }
+
+    /// Pairs every value with the most recent value of another observable.
+    ///
+    /// For every value produced, the pair of the value and the most recent
+    /// value of `other` is pushed. If `other` has not produced a value yet,
+    /// the value is dropped. The observable completes when this observable
+    /// completes. If either of the observables fails, the error is passed
+    /// through immediately.
+    fn with_latest_from<'s, ObOther>(&'s mut self, other: &'s mut ObOther) -> WithLatestFromObservable<'s, Self, ObOther>
+        where ObOther: Observable<Error = Self::Error> {
+        WithLatestFromObservable::new(self, other)
+    }
+
+    /// Turns the calls to the observer into values.
+    ///
+    /// Every value `x` is pushed as `Notification::Next(x)`. When the
+    /// observable completes, `Notification::Completed` is pushed, and when it
+    /// fails, `Notification::Error(error)` is pushed. In both cases the
+    /// materialized observable completes afterwards; it never fails itself.
+    fn materialize<'s>(&'s mut self) -> MaterializeObservable<'s, Self> {
+        MaterializeObservable::new(self)
+    }
+
+    /// Turns values that describe calls to an observer into those calls.
+    ///
+    /// This is the inverse of [`materialize()`](#method.materialize).
+    /// `Notification::Next(x)` pushes `x`, `Notification::Completed` completes,
+    /// and `Notification::Error(error)` fails with the error. Notifications
+    /// after a terminal one are ignored. If the observable completes without
+    /// a terminal notification, the result completes too. Because the error
+    /// type of the observable is `()`, its failure carries no information,
+    /// and it is treated as completion.
+    fn dematerialize<'s, T, E>(&'s mut self) -> DematerializeObservable<'s, Self, T, E>
+        where Self: Observable<Item = Notification<T, E>, Error = ()>, T: Clone, E: Clone {
+        DematerializeObservable::new(self)
+    }
+
+    /// Shares a single subscription to the observable between all observers.
+    ///
+    /// The observable is subscribed to when the first observer subscribes, and
+    /// the values it pushes are pushed to all observers that are subscribed at
+    /// that point. When the last subscription is dropped, the subscription to
+    /// the observable is dropped too. If the observable terminates, the next
+    /// observer to subscribe subscribes to it again.
+    ///
+    /// Note that an observable that pushes its values upon subscription, such
+    /// as a slice, pushes all of them to the first observer before any other
+    /// observer can subscribe.
+    fn share<'s>(&'s mut self) -> SharedObservable<'s, Self> {
+        SharedObservable::new(self)
+    }
+
+    /// Returns an observable that does not subscribe to the source until `connect()` is called.
+    ///
+    /// Observers that subscribe to the returned observable receive nothing
+    /// until the observable is connected. This makes it possible to subscribe
+    /// multiple observers before the source starts pushing values. Unlike
+    /// `share()`, the observable connects only once.
+    fn publish<'s>(&'s mut self) -> ConnectableObservable<'s, Self> {
+        ConnectableObservable::new(self)
+    }
+
+    /// Subscribes to the observable once, and replays its values to every observer.
+    ///
+    /// The observable is subscribed to when the first observer subscribes, and
+    /// everything it pushes is recorded. Later observers first receive the
+    /// recorded values (and completion or failure, if the observable
+    /// terminated), and then the values that the observable pushes after
+    /// that. The observable is never subscribed to again. The subscription to
+    /// the observable lives as long as the cached observable.
+    fn cache<'s>(&'s mut self) -> CacheObservable<'s, Self> {
+        CacheObservable::new(self)
+    }
+
+    /// Determines whether two observables produce equal values.
+    ///
+    /// Values are compared pairwise in the order in which they are produced.
+    /// As soon as a pair differs, or one observable completes while the other
+    /// has produced more values, `false` is pushed, followed by completion. If
+    /// both complete after producing equal values, `true` is pushed, followed
+    /// by completion. If either fails, the error is passed through. Values of
+    /// the observable that is ahead are buffered until they can be compared.
+    fn sequence_equal<'s, ObOther>(&'s mut self, other: &'s mut ObOther) -> SequenceEqualObservable<'s, Self, ObOther>
+        where Self::Item: PartialEq, ObOther: Observable<Item = Self::Item, Error = Self::Error> {
+        SequenceEqualObservable::new(self, other)
+    }
+
+    /// Erases the type of the observable.
+    ///
+    /// This is useful to store observables of different types in the same
+    /// place, or to avoid spelling out deeply nested observable types.
+    fn boxed<'a>(self) -> BoxObservable<'a, Self::Item, Self::Error>
+        where Self: Sized + 'a {
+        BoxObservable::new(self)
+    }
+}
+
+/// An observable that can be subscribed to through a shared reference.
+///
+/// Observables that do not need to modify themselves upon subscription, such
+/// as options, results, and slices, implement this trait in addition to
+/// `Observable`. For these, `subscribe()` is equivalent to `subscribe_ref()`.
+pub trait ImmutableObservable: Observable {
+    /// Subscribes an observer and returns the subscription.
+    ///
+    /// This is like [`subscribe()`](trait.Observable.html#tymethod.subscribe),
+    /// but it does not require a mutable reference.
+    fn subscribe_ref<O>(&self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error>;
+
+    /// Subscribes a function to handle values produced by the observable.
+    ///
+    /// This is like [`subscribe_next()`](trait.Observable.html#method.subscribe_next),
+    /// but it does not require a mutable reference.
+    ///
+    /// **This subscription panics if the observable fails with an error.**
+    fn subscribe_ref_next<FnNext>(&self,
+                                  on_next: FnNext)
+                                  -> Self::Subscription
+        where Self::Error: Debug, FnNext: FnMut(Self::Item) {
+        let observer = NextObserver {
+            fn_next: on_next,
+        };
+        self.subscribe_ref(observer)
+    }
+
+    /// Subscribes functions to handle next and completion.
+    ///
+    /// This is like [`subscribe_completed()`](trait.Observable.html#method.subscribe_completed),
+    /// but it does not require a mutable reference.
+    ///
+    /// **This subscription panics if the observable fails with an error.**
+    fn subscribe_ref_completed<FnNext, FnCompleted>(&self,
+                                                    on_next: FnNext,
+                                                    on_completed: FnCompleted)
+                                                    -> Self::Subscription
+        where Self::Error: Debug, FnNext: FnMut(Self::Item), FnCompleted: FnOnce() {
+        let observer = CompletedObserver {
+            fn_next: on_next,
+            fn_completed: on_completed,
+        };
+        self.subscribe_ref(observer)
+    }
 }
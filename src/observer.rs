@@ -6,6 +6,8 @@
 // A copy of the License has been included in the root of the repository.
 
 use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::mpsc::Sender;
 
 /// An observer that receives values from an observable.
 pub trait Observer<T, E> {
@@ -19,6 +21,17 @@ pub trait Observer<T, E> {
     fn on_error(self, error: E);
 }
 
+/// A call to an observer, reified as a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Notification<T, E> {
+    /// A call to `on_next()` with the value.
+    Next(T),
+    /// A call to `on_completed()`.
+    Completed,
+    /// A call to `on_error()` with the error.
+    Error(E),
+}
+
 pub struct NextObserver<FnNext> {
     pub fn_next: FnNext,
 }
@@ -42,6 +55,25 @@ pub struct ResultObserver<FnResult> {
     pub fn_result: FnResult
 }
 
+/// An observer that sends the values it receives into a channel.
+///
+/// Completion and failure drop the sender, which closes the channel if this
+/// was the last sender. If the receiver has been dropped, the values are
+/// discarded.
+pub struct ChannelObserver<T> {
+    // This is `None` after sending failed.
+    sender: Option<Sender<T>>,
+}
+
+impl<T> ChannelObserver<T> {
+    /// Creates an observer that sends its values into the channel.
+    pub fn new(sender: Sender<T>) -> ChannelObserver<T> {
+        ChannelObserver {
+            sender: Some(sender),
+        }
+    }
+}
+
 impl<T, E, FnNext> Observer<T, E> for NextObserver<FnNext>
     where E: Debug, FnNext: FnMut(T) {
 
@@ -123,6 +155,93 @@ impl<T, E, FnResult> Observer<T, E> for ResultObserver<FnResult>
     }
 }
 
+impl<T, E> Observer<T, E> for ChannelObserver<T> {
+    fn on_next(&mut self, item: T) {
+        let failed = match self.sender {
+            Some(ref sender) => sender.send(item).is_err(),
+            None => false,
+        };
+        if failed {
+            // The receiver was dropped, stop sending.
+            self.sender = None;
+        }
+    }
+
+    fn on_completed(self) {
+        // Dropping self drops the sender.
+    }
+
+    fn on_error(self, _error: E) {
+        // The error cannot be sent, only dropping the sender signals the end.
+    }
+}
+
+/// Pushes every value onto the vector.
+///
+/// Like the observer of `subscribe_next()`, completion is ignored, and the
+/// observer panics when the observable fails.
+impl<'a, T, E> Observer<T, E> for &'a mut Vec<T> where E: Debug {
+    fn on_next(&mut self, item: T) {
+        self.push(item);
+    }
+
+    fn on_completed(self) {
+        // Ignore completion.
+    }
+
+    fn on_error(self, error: E) {
+        panic!("observer received error: {:?}", error);
+    }
+}
+
+/// An observer that applies a function to values before passing them on.
+///
+/// This is a building block for implementing observables, it is not needed
+/// to use them. See [`map_observer()`](fn.map_observer.html).
+pub struct MapObserver<T, U, E, O, F>
+where O: Observer<U, E>,
+      F: Fn(T) -> U {
+    observer: O,
+    f: F,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_u: PhantomData<*mut U>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+/// Adapts an observer of `U` into an observer of `T`.
+///
+/// The returned observer passes `f(x)` on to `observer` for every value `x`,
+/// and passes completion and failure through unchanged. This is intended for
+/// authors of observables, who need to subscribe an observer to a source with
+/// a different item type. To transform the values of an observable, use
+/// [`Observable::map()`](trait.Observable.html#method.map) instead.
+pub fn map_observer<T, U, E, O, F>(observer: O, f: F) -> MapObserver<T, U, E, O, F>
+    where O: Observer<U, E>, F: Fn(T) -> U {
+    MapObserver {
+        observer: observer,
+        f: f,
+        _phantom_t: PhantomData,
+        _phantom_u: PhantomData,
+        _phantom_e: PhantomData,
+    }
+}
+
+impl<T, U, E, O, F> Observer<T, E> for MapObserver<T, U, E, O, F>
+where O: Observer<U, E>,
+      F: Fn(T) -> U {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(self.f.call((item,)));
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
 /// Trait that enables using `Observer` as a trait object.
 ///
 /// The methods `on_completed()` and `on_error()` cannot be called on trait objects,
@@ -147,3 +266,17 @@ impl<O, T, E> BoxedObserver<T, E> for O where O: Observer<T, E> {
         self.on_error(error);
     }
 }
+
+impl<'o, T, E> Observer<T, E> for Box<BoxedObserver<T, E> + 'o> {
+    fn on_next(&mut self, item: T) {
+        (**self).on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.on_completed_box();
+    }
+
+    fn on_error(self, error: E) {
+        self.on_error_box(error);
+    }
+}
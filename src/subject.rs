@@ -8,14 +8,33 @@
 use lifeline;
 use observable::Observable;
 use observer::{Observer, BoxedObserver};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use subscription::Subscription;
+
+/// The observers of a subject, see `SubjectState`.
+type Observers<T, E> = Vec<lifeline::Owner<Box<BoxedObserver<T, E>>>>;
+
+/// The state of a subject, shared between the subject and its handles.
+struct SubjectState<T, E> {
+    observers: Observers<T, E>,
+    /// How the subject terminated, `None` while it is active.
+    terminal: Option<Terminal<E>>,
+}
 
 /// Both an observer and observable.
 ///
 /// A subject is a low-level primitive for creating observables.
 ///
+/// After the subject completes or fails, values pushed into it are ignored,
+/// and observers that subscribe afterwards complete or fail immediately.
+///
 /// TODO: Add example.
 pub struct Subject<T, E> {
-    observers: Vec<lifeline::Owner<Box<BoxedObserver<T, E>>>>,
+    state: Rc<RefCell<SubjectState<T, E>>>,
+    /// Keeps the observers passed to `from_observers()` subscribed.
+    permanent: Vec<lifeline::Lifeline<Box<BoxedObserver<T, E>>>>,
 }
 
 /// Proxy object that exposes the observable part of a subject.
@@ -23,19 +42,79 @@ pub struct SubjectObservable<'s, T: 's, E: 's> {
     subject: &'s mut Subject<T, E>,
 }
 
+/// An observable that subscribes to a subject without borrowing it.
+///
+/// See [`Subject::handle()`](struct.Subject.html#method.handle).
+pub struct SubjectHandle<T, E> {
+    state: Rc<RefCell<SubjectState<T, E>>>,
+}
+
+/// The subscription of an observer to a subject.
+///
+/// Dropping the subscription unsubscribes the observer.
 pub struct SubjectSubscription<T, E> {
     #[allow(dead_code)] // This code is not dead, it keeps the observer alive.
-    alive: lifeline::Lifeline<Box<BoxedObserver<T, E>>>,
+    alive: Option<lifeline::Lifeline<Box<BoxedObserver<T, E>>>>,
+}
+
+/// An observer that maps values before pushing them into a subject.
+///
+/// See [`Subject::contramap()`](struct.Subject.html#method.contramap).
+pub struct SubjectSink<'s, T: 's, U, E: 's, F> {
+    subject: &'s mut Subject<T, E>,
+    f: F,
+    _phantom_u: PhantomData<*mut U>,
+}
+
+/// A subject that remembers the most recent value.
+///
+/// Upon subscription, the observer immediately receives the current value,
+/// and after that it receives all values pushed into the subject, like with a
+/// regular `Subject`. When the subject has terminated, a new observer receives
+/// the completion or error instead.
+pub struct BehaviorSubject<T, E> {
+    subject: Subject<T, E>,
+    value: T,
+}
+
+/// Proxy object that exposes the observable part of a behavior subject.
+pub struct BehaviorSubjectObservable<'s, T: 's, E: 's> {
+    subject: &'s mut BehaviorSubject<T, E>,
+}
+
+/// The way in which a subject terminated.
+enum Terminal<E> {
+    Completed,
+    Failed(E),
 }
 
 impl<T, E> Subject<T, E> {
     /// Creates a new subject.
     pub fn new() -> Subject<T, E> {
         Subject {
-            observers: Vec::new(),
+            state: Rc::new(RefCell::new(SubjectState {
+                observers: Vec::new(),
+                terminal: None,
+            })),
+            permanent: Vec::new(),
         }
     }
 
+    /// Creates a new subject with the given observers subscribed.
+    ///
+    /// There are no subscriptions for these observers: they stay subscribed
+    /// for the lifetime of the subject, until it completes or fails. Handles
+    /// obtained with `handle()` do not keep them subscribed.
+    pub fn from_observers(observers: Vec<Box<BoxedObserver<T, E>>>) -> Subject<T, E> {
+        let mut subject = Subject::new();
+        for observer in observers {
+            let (alive, owner) = lifeline::new(observer);
+            subject.state.borrow_mut().observers.push(owner);
+            subject.permanent.push(alive);
+        }
+        subject
+    }
+
     /// Returns a proxy object that exposes the observable part of a subject.
     ///
     /// This can be used to avoid exposing the observer methods while still
@@ -46,30 +125,145 @@ impl<T, E> Subject<T, E> {
             subject: self,
         }
     }
+
+    /// Returns an observable that subscribes to the subject without borrowing it.
+    ///
+    /// Unlike the proxy returned by `observable()`, the handle can be stored
+    /// and cloned, and it can be used to subscribe while the subject is
+    /// pushing a value, for instance from within one of its observers. Such an
+    /// observer will not receive the value that is being pushed, only the
+    /// values after it.
+    pub fn handle(&self) -> SubjectHandle<T, E> {
+        SubjectHandle {
+            state: self.state.clone(),
+        }
+    }
+
+    /// Returns the number of observers subscribed to the subject.
+    ///
+    /// This counts the subscriptions that have not been dropped yet. It does
+    /// not include observers that have been terminated, because the subject
+    /// releases those when it completes or fails.
+    pub fn subscriber_count(&self) -> usize {
+        self.state.borrow().observers.iter().filter(|owner| owner.is_alive()).count()
+    }
 }
 
-impl<T: Clone, E: Clone> Observer<T, E> for Subject<T, E> {
-    fn on_next(&mut self, item: T) {
-        let mut remove_indices = Vec::new();
-        let mut i = 0;
-        for observer_owner in &mut self.observers {
+impl<T, E> Subject<T, E> {
+    /// Returns whether the subject has completed or failed.
+    fn is_terminated(&self) -> bool {
+        self.state.borrow().terminal.is_some()
+    }
+
+    /// Calls `f` on every observer whose subscription has not been dropped.
+    ///
+    /// Observers whose subscription was dropped are removed from the list.
+    fn for_each_observer<F>(&mut self, mut f: F) where F: FnMut(&mut Box<BoxedObserver<T, E>>) {
+        // Invoking an observer might subscribe a new observer or drop a
+        // subscription, so iterate over a snapshot rather than over the list
+        // itself. Observers that subscribe during the iteration are appended to
+        // the list, so they are not invoked, and the indices of the existing
+        // observers remain valid.
+        let mut snapshot = self.state.borrow().observers.clone();
+        let mut dead = vec![false; snapshot.len()];
+        let mut any_dead = false;
+        for (i, observer_owner) in snapshot.iter_mut().enumerate() {
             observer_owner.with_mut_value_or(|observer| {
                 // The subscription was not dropped, invoke the method.
-                observer.on_next(item.clone());
+                f(observer);
             }, || {
                 // The subscription was dropped, ignore the observer next time.
-                remove_indices.push(i);
+                dead[i] = true;
+                any_dead = true;
+            });
+        }
+
+        // Remove the dead observers in a single pass. Observers that
+        // subscribed during the iteration are beyond the end of the snapshot,
+        // and they are alive.
+        if any_dead {
+            let mut i = 0;
+            self.state.borrow_mut().observers.retain(|_| {
+                let keep = i >= dead.len() || !dead[i];
+                i += 1;
+                keep
             });
-            i += 1;
         }
+    }
+}
+
+impl<T: Clone, E> Subject<T, E> {
+    /// Pushes all values in the slice to the observers.
+    ///
+    /// Every observer receives the same values in the same order as with
+    /// repeated calls to `on_next()`, but this is faster for many values,
+    /// because the bookkeeping per observer is done once for the entire batch.
+    /// The difference is that the first observer receives all values before
+    /// the second observer receives any, and so on. An observer that
+    /// subscribes during the batch receives none of its values.
+    pub fn on_next_many(&mut self, items: &[T]) {
+        self.for_each_observer(|observer| {
+            for item in items {
+                observer.on_next(item.clone());
+            }
+        });
+    }
+}
 
-        for &rm_i in remove_indices.iter().rev() {
-            self.observers.remove(rm_i);
+/// Registers the observer with the subject, and returns its subscription.
+///
+/// If the subject terminated already, the observer is terminated immediately
+/// in the same way instead.
+fn subscribe_to<T, E, O: 'static>(state: &Rc<RefCell<SubjectState<T, E>>>, observer: O) -> SubjectSubscription<T, E>
+    where E: Clone, O: Observer<T, E> {
+    // The state must not be borrowed while the observer is invoked.
+    let terminal = match state.borrow().terminal {
+        Some(Terminal::Completed) => Some(Terminal::Completed),
+        Some(Terminal::Failed(ref error)) => Some(Terminal::Failed(error.clone())),
+        None => None,
+    };
+    match terminal {
+        Some(Terminal::Completed) => observer.on_completed(),
+        Some(Terminal::Failed(error)) => observer.on_error(error),
+        None => {
+            let boxed: Box<BoxedObserver<T, E>> = Box::new(observer);
+            let (alive, owner) = lifeline::new(boxed);
+            state.borrow_mut().observers.push(owner);
+            return SubjectSubscription {
+                alive: Some(alive),
+            };
         }
     }
 
-    fn on_completed(mut self) {
-        for observer_owner in self.observers.drain(..) {
+    // The observer has terminated already, there is nothing to keep alive.
+    SubjectSubscription {
+        alive: None,
+    }
+}
+
+/// Marks the subject as terminated, and takes out the observers to terminate.
+///
+/// Returns no observers if the subject terminated before.
+fn terminate<T, E>(state: &Rc<RefCell<SubjectState<T, E>>>, terminal: Terminal<E>) -> Observers<T, E> {
+    use std::mem;
+    // The list must not be borrowed while the observers are invoked, because
+    // they might subscribe new observers, so take them out.
+    let mut state = state.borrow_mut();
+    if state.terminal.is_some() {
+        return Vec::new();
+    }
+    state.terminal = Some(terminal);
+    mem::replace(&mut state.observers, Vec::new())
+}
+
+impl<T: Clone, E: Clone> Subject<T, E> {
+    /// Completes all observers, without consuming the subject.
+    ///
+    /// The observers are unsubscribed after they complete. This makes it
+    /// possible to complete a subject through a mutable reference. This has
+    /// no effect if the subject terminated before.
+    pub fn complete(&mut self) {
+        for observer_owner in terminate(&self.state, Terminal::Completed) {
             if let Some(observer) = observer_owner.take() {
                 // The subscription was not dropped, invoke the method.
                 observer.on_completed_box();
@@ -77,14 +271,101 @@ impl<T: Clone, E: Clone> Observer<T, E> for Subject<T, E> {
         }
     }
 
-    fn on_error(mut self, error: E) {
-        for observer_owner in self.observers.drain(..) {
+    /// Fails all observers with the error, without consuming the subject.
+    ///
+    /// The observers are unsubscribed after they fail. This makes it possible
+    /// to fail a subject through a mutable reference. This has no effect if
+    /// the subject terminated before.
+    pub fn fail(&mut self, error: E) {
+        for observer_owner in terminate(&self.state, Terminal::Failed(error.clone())) {
             if let Some(observer) = observer_owner.take() {
                 // The subscription was not dropped, invoke the method.
                 observer.on_error_box(error.clone());
             }
         }
     }
+
+    /// Completes all observers, without consuming the subject.
+    ///
+    /// This is the same as `complete()`, and it is preferred over the
+    /// consuming `Observer::on_completed()`. Note that for a subject held by
+    /// value, method call syntax resolves to the trait method when `Observer`
+    /// is in scope; both notify the observers in the same way.
+    pub fn on_completed(&mut self) {
+        self.complete();
+    }
+
+    /// Fails all observers with the error, without consuming the subject.
+    ///
+    /// This is the same as `fail()`, and it is preferred over the consuming
+    /// `Observer::on_error()`. The note on `on_completed()` applies here too.
+    pub fn on_error(&mut self, error: E) {
+        self.fail(error);
+    }
+}
+
+impl<T: Clone, E: Clone> Subject<T, E> {
+    /// Returns an observer that pushes `f(x)` into the subject for every value `x`.
+    ///
+    /// This adapts the subject to a source with a different item type.
+    /// Completion and failure are passed on through `complete()` and
+    /// `fail()`, so the subject is not consumed.
+    pub fn contramap<'s, U, F>(&'s mut self, f: F) -> SubjectSink<'s, T, U, E, F>
+        where F: FnMut(U) -> T {
+        SubjectSink {
+            subject: self,
+            f: f,
+            _phantom_u: PhantomData,
+        }
+    }
+}
+
+impl<T: Clone, E: Clone> Observer<T, E> for Subject<T, E> {
+    fn on_next(&mut self, item: T) {
+        self.for_each_observer(|observer| observer.on_next(item.clone()));
+    }
+
+    fn on_completed(mut self) {
+        self.complete();
+    }
+
+    fn on_error(mut self, error: E) {
+        self.fail(error);
+    }
+}
+
+/// A mutable reference to a subject is an observer too.
+///
+/// Completion and failure are passed on through `complete()` and `fail()`, so
+/// the subject is not consumed.
+impl<'s, T: Clone, E: Clone> Observer<T, E> for &'s mut Subject<T, E> {
+    fn on_next(&mut self, item: T) {
+        (**self).on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.complete();
+    }
+
+    fn on_error(self, error: E) {
+        self.fail(error);
+    }
+}
+
+impl<'s, T: Clone, U, E: Clone, F> Observer<U, E> for SubjectSink<'s, T, U, E, F>
+where F: FnMut(U) -> T {
+    fn on_next(&mut self, item: U) {
+        let mapped = self.f.call_mut((item,));
+        Observer::on_next(&mut *self.subject, mapped);
+    }
+
+    fn on_completed(self) {
+        self.subject.complete();
+    }
+
+    fn on_error(self, error: E) {
+        self.subject.fail(error);
+    }
 }
 
 impl<'s, T: Clone, E: Clone> Observable for SubjectObservable<'s, T, E> {
@@ -94,17 +375,110 @@ impl<'s, T: Clone, E: Clone> Observable for SubjectObservable<'s, T, E> {
 
     fn subscribe<O: 'static>(&mut self, observer: O) -> Self::Subscription
         where O: Observer<Self::Item, Self::Error> {
-        let boxed: Box<BoxedObserver<T, E>> = Box::new(observer);
-        let (alive, owner) = lifeline::new(boxed);
-        self.subject.observers.push(owner);
-        SubjectSubscription {
-            alive: alive,
+        subscribe_to(&self.subject.state, observer)
+    }
+}
+
+impl<T, E> Clone for SubjectHandle<T, E> {
+    fn clone(&self) -> SubjectHandle<T, E> {
+        SubjectHandle {
+            state: self.state.clone(),
         }
     }
 }
 
-impl<T, E> Drop for SubjectSubscription<T, E> {
-    fn drop(&mut self) {
-        // Nothing to do, the Rc already does the right thing.
+impl<T: Clone, E: Clone> Observable for SubjectHandle<T, E> {
+    type Item = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O: 'static>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        subscribe_to(&self.state, observer)
+    }
+}
+
+impl<T, E> SubjectSubscription<T, E> {
+    /// Unsubscribes the observer, so the subject does not call it any more.
+    ///
+    /// This is the same as `Subscription::unsubscribe()`, and it is available
+    /// without importing the trait. The observer is dropped immediately.
+    pub fn unsubscribe(self) {
+        Subscription::unsubscribe(self)
+    }
+}
+
+impl<T, E> Subscription for SubjectSubscription<T, E> { }
+
+impl<T, E> BehaviorSubject<T, E> {
+    /// Creates a new behavior subject with the given initial value.
+    pub fn new(initial: T) -> BehaviorSubject<T, E> {
+        BehaviorSubject {
+            subject: Subject::new(),
+            value: initial,
+        }
+    }
+
+    /// Returns a proxy object that exposes the observable part of the subject.
+    pub fn observable<'s>(&'s mut self) -> BehaviorSubjectObservable<'s, T, E> {
+        BehaviorSubjectObservable {
+            subject: self,
+        }
+    }
+
+    /// Returns the most recent value pushed into the subject.
+    ///
+    /// This is the initial value if no value has been pushed yet.
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+}
+
+impl<T: Clone, E: Clone> BehaviorSubject<T, E> {
+    /// Completes all observers, without consuming the subject.
+    ///
+    /// Observers that subscribe afterwards complete immediately.
+    pub fn complete(&mut self) {
+        self.subject.complete();
+    }
+
+    /// Fails all observers with the error, without consuming the subject.
+    ///
+    /// Observers that subscribe afterwards fail immediately with the error.
+    pub fn fail(&mut self, error: E) {
+        self.subject.fail(error);
+    }
+}
+
+impl<T: Clone, E: Clone> Observer<T, E> for BehaviorSubject<T, E> {
+    fn on_next(&mut self, item: T) {
+        if !self.subject.is_terminated() {
+            self.value = item.clone();
+            self.subject.on_next(item);
+        }
+    }
+
+    fn on_completed(mut self) {
+        self.complete();
+    }
+
+    fn on_error(mut self, error: E) {
+        self.fail(error);
+    }
+}
+
+impl<'s, T: Clone, E: Clone> Observable for BehaviorSubjectObservable<'s, T, E> {
+    type Item = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O>(&mut self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        // After termination, the subject completes or fails the observer
+        // immediately, without the current value.
+        if !self.subject.subject.is_terminated() {
+            observer.on_next(self.subject.value.clone());
+        }
+        self.subject.subject.observable().subscribe(observer)
     }
 }
@@ -0,0 +1,57 @@
+// Rx -- Reactive programming for Rust
+// Copyright 2016 Ruud van Asseldonk
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// A copy of the License has been included in the root of the repository.
+
+/// The result of subscribing an observer to an observable.
+///
+/// Every subscription type implements this trait, so subscriptions of
+/// different observables can be stored together as `Box<Subscription>`.
+pub trait Subscription {
+    /// Unsubscribes the observer, so the observable does not call it any more.
+    ///
+    /// This is equivalent to dropping the subscription, but it states the
+    /// intent explicitly. For subscriptions that cannot be cancelled, this is
+    /// a no-op.
+    fn unsubscribe(self) where Self: Sized {
+        drop(self);
+    }
+}
+
+/// The unit subscription, for observables that cannot be cancelled.
+impl Subscription for () { }
+
+impl<'a> Subscription for Box<Subscription + 'a> { }
+
+/// A subscription that calls a function after the wrapped subscription is dropped.
+///
+/// This attaches cleanup to any subscription, regardless of whether the
+/// observable supports it. The function is called exactly once, when the
+/// wrapper is dropped, after the wrapped subscription has been dropped.
+pub struct OnUnsubscribe<S, F: FnOnce()> {
+    subscription: Option<S>,
+    f: Option<F>,
+}
+
+impl<S, F: FnOnce()> OnUnsubscribe<S, F> {
+    /// Wraps the subscription, to call f after it has been dropped.
+    pub fn new(subscription: S, f: F) -> OnUnsubscribe<S, F> {
+        OnUnsubscribe {
+            subscription: Some(subscription),
+            f: Some(f),
+        }
+    }
+}
+
+impl<S, F: FnOnce()> Drop for OnUnsubscribe<S, F> {
+    fn drop(&mut self) {
+        drop(self.subscription.take());
+        if let Some(f) = self.f.take() {
+            f();
+        }
+    }
+}
+
+impl<S: Subscription, F: FnOnce()> Subscription for OnUnsubscribe<S, F> { }
@@ -7,12 +7,140 @@
 
 use lifeline;
 use observable::Observable;
-use observer::Observer;
+use observer::{Notification, Observer, map_observer};
+use subject::{Subject, SubjectHandle, SubjectSubscription};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
 use std::marker::PhantomData;
+use std::ops::Add;
+use std::rc::{Rc, Weak};
+use subscription::{OnUnsubscribe, Subscription};
 
-struct MapObserver<T, U, E, O, F>
+/// The result of calling `map()` on an observable.
+pub struct MapObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F
+}
+
+impl<'a, Source: 'a + ?Sized, F> MapObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> MapObservable<'a, Source, F> {
+        MapObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, U, F> Observable for MapObservable<'a, Source, F>
+where Source: Observable,
+      U: Clone,
+      F: Fn(<Source as Observable>::Item) -> U {
+    type Item = U;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.source.subscribe(map_observer(observer, &self.f))
+    }
+}
+
+/// The result of calling `map_to()` on an observable.
+pub struct MapToObservable<'a, Source: 'a + ?Sized, U> {
+    source: &'a mut Source,
+    value: U,
+}
+
+impl<'a, Source: 'a + ?Sized, U> MapToObservable<'a, Source, U> {
+    pub fn new(source: &'a mut Source, value: U) -> MapToObservable<'a, Source, U> {
+        MapToObservable {
+            source: source,
+            value: value,
+        }
+    }
+}
+
+impl<'a, Source, U> Observable for MapToObservable<'a, Source, U>
+where Source: Observable,
+      U: Clone {
+    type Item = U;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let value = &self.value;
+        self.source.subscribe(map_observer(observer, move |_item| value.clone()))
+    }
+}
+
+struct FilterObserver<T, E, O, P>
+where O: Observer<T, E>,
+      P: Fn(&T) -> bool {
+    observer: O,
+    predicate: P,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, P> Observer<T, E> for FilterObserver<T, E, O, P>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      P: Fn(&T) -> bool {
+    fn on_next(&mut self, item: T) {
+        if self.predicate.call((&item,)) {
+            self.observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `filter()` on an observable.
+pub struct FilterObservable<'a, Source: 'a + ?Sized, P> {
+    source: &'a mut Source,
+    predicate: P,
+}
+
+impl<'a, Source: 'a + ?Sized, P> FilterObservable<'a, Source, P> {
+    pub fn new(source: &'a mut Source, predicate: P) -> FilterObservable<'a, Source, P> {
+        FilterObservable {
+            source: source,
+            predicate: predicate,
+        }
+    }
+}
+
+impl<'a, Source, P> Observable for FilterObservable<'a, Source, P>
+where Source: Observable,
+      P: Fn(&<Source as Observable>::Item) -> bool {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let filtered_observer = FilterObserver {
+            observer: observer,
+            predicate: &self.predicate,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(filtered_observer)
+    }
+}
+
+struct FilterMapObserver<T, U, E, O, F>
 where O: Observer<U, E>,
-      F: Fn(T) -> U {
+      F: Fn(T) -> Option<U> {
     observer: O,
     f: F,
     _phantom_t: PhantomData<*mut T>,
@@ -20,14 +148,13 @@ where O: Observer<U, E>,
     _phantom_e: PhantomData<*mut E>,
 }
 
-impl<T, U, E, O, F> Observer<T, E> for MapObserver<T, U, E, O, F>
-where T: Clone,
-      U: Clone,
-      E: Clone,
-      O: Observer<U, E>,
-      F: Fn(T) -> U {
+impl<T, U, E, O, F> Observer<T, E> for FilterMapObserver<T, U, E, O, F>
+where O: Observer<U, E>,
+      F: Fn(T) -> Option<U> {
     fn on_next(&mut self, item: T) {
-        self.observer.on_next(self.f.call((item,)));
+        if let Some(value) = self.f.call((item,)) {
+            self.observer.on_next(value);
+        }
     }
 
     fn on_completed(self) {
@@ -39,39 +166,39 @@ where T: Clone,
     }
 }
 
-/// The result of calling `map()` on an observable.
-pub struct MapObservable<'a, Source: 'a + ?Sized, F> {
+/// The result of calling `filter_map()` on an observable.
+pub struct FilterMapObservable<'a, Source: 'a + ?Sized, F> {
     source: &'a mut Source,
-    f: F
+    f: F,
 }
 
-impl<'a, Source: 'a + ?Sized, F> MapObservable<'a, Source, F> {
-    pub fn new(source: &'a mut Source, f: F) -> MapObservable<'a, Source, F> {
-        MapObservable {
+impl<'a, Source: 'a + ?Sized, F> FilterMapObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> FilterMapObservable<'a, Source, F> {
+        FilterMapObservable {
             source: source,
             f: f,
         }
     }
 }
 
-impl<'a, Source, U, F> Observable for MapObservable<'a, Source, F>
+impl<'a, Source, U, F> Observable for FilterMapObservable<'a, Source, F>
 where Source: Observable,
       U: Clone,
-      F: Fn(<Source as Observable>::Item) -> U {
+      F: Fn(<Source as Observable>::Item) -> Option<U> {
     type Item = U;
     type Error = <Source as Observable>::Error;
     type Subscription = <Source as Observable>::Subscription;
 
     fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
         where O: Observer<Self::Item, Self::Error> {
-        let mapped_observer = MapObserver {
+        let filter_map_observer = FilterMapObserver {
             observer: observer,
             f: &self.f,
             _phantom_t: PhantomData,
             _phantom_u: PhantomData,
             _phantom_e: PhantomData,
         };
-        self.source.subscribe(mapped_observer)
+        self.source.subscribe(filter_map_observer)
     }
 }
 
@@ -153,11 +280,7 @@ pub struct ContinueWithSubscription<Source: Observable, ObNext: Observable> {
     subs_next: lifeline::Lifeline<Option<ObNext::Subscription>>,
 }
 
-impl<Source: Observable, ObNext: Observable> Drop for ContinueWithSubscription<Source, ObNext> {
-    fn drop(&mut self) {
-        // This is a no-op, the lifeline handles everything automatically.
-    }
-}
+impl<Source: Observable, ObNext: Observable> Subscription for ContinueWithSubscription<Source, ObNext> { }
 
 struct ContinueWithObserver<'a, T: Clone, E: Clone, ObNext: 'a, O>
 where ObNext: Observable<Item = T, Error = E>,
@@ -226,3 +349,5588 @@ where Source: Observable<Item = T, Error = E>,
         }
     }
 }
+
+/// The result of calling `chain()` on an observable.
+pub struct ChainObservable<'a, Source: 'a + ?Sized, ObNext> {
+    source: &'a mut Source,
+    next: ObNext,
+}
+
+impl<'a, Source: 'a + ?Sized, ObNext> ChainObservable<'a, Source, ObNext> {
+    pub fn new(source: &'a mut Source, next: ObNext) -> ChainObservable<'a, Source, ObNext> {
+        ChainObservable {
+            source: source,
+            next: next,
+        }
+    }
+}
+
+impl<'a, T: Clone, E: Clone, Source, ObNext> Observable for ChainObservable<'a, Source, ObNext>
+where Source: Observable<Item = T, Error = E>,
+      ObNext: Observable<Item = T, Error = E> {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ContinueWithSubscription<Source, ObNext>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (life, owner) = lifeline::new(None);
+        let continued_observer = ContinueWithObserver {
+            observer: observer,
+            next: &mut self.next,
+            subscription: owner,
+        };
+        let subs_source = self.source.subscribe(continued_observer);
+        ContinueWithSubscription {
+            subs_source: subs_source,
+            subs_next: life,
+        }
+    }
+}
+
+/// The result of calling `start_with_observable()` on an observable.
+///
+/// This is the observable returned by `head.continue_with(source)`.
+pub type StartWithObservableObservable<'a, Source, ObHead> = ContinueWithObservable<'a, ObHead, Source>;
+
+struct SkipObserver<T, E, O>
+where O: Observer<T, E> {
+    observer: O,
+    remaining: usize,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for SkipObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+        } else {
+            self.observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `skip()` on an observable.
+pub struct SkipObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    n: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> SkipObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, n: usize) -> SkipObservable<'a, Source> {
+        SkipObservable {
+            source: source,
+            n: n,
+        }
+    }
+}
+
+impl<'a, Source> Observable for SkipObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        // Every observer gets its own counter, so every subscription skips
+        // the first `n` values it receives.
+        let skip_observer = SkipObserver {
+            observer: observer,
+            remaining: self.n,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(skip_observer)
+    }
+}
+
+struct ScanObserver<T, E, A, O, F>
+where O: Observer<A, E>,
+      F: Fn(A, T) -> A {
+    observer: O,
+    accumulator: A,
+    f: F,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, A, O, F> Observer<T, E> for ScanObserver<T, E, A, O, F>
+where T: Clone,
+      E: Clone,
+      A: Clone,
+      O: Observer<A, E>,
+      F: Fn(A, T) -> A {
+    fn on_next(&mut self, item: T) {
+        self.accumulator = self.f.call((self.accumulator.clone(), item));
+        self.observer.on_next(self.accumulator.clone());
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `scan()` or `scan_seeded()` on an observable.
+pub struct ScanObservable<'a, Source: 'a + ?Sized, A, F> {
+    source: &'a mut Source,
+    initial: A,
+    f: F,
+    /// Whether to push the initial accumulator upon subscription.
+    push_initial: bool,
+}
+
+impl<'a, Source: 'a + ?Sized, A, F> ScanObservable<'a, Source, A, F> {
+    pub fn new(source: &'a mut Source, initial: A, f: F) -> ScanObservable<'a, Source, A, F> {
+        ScanObservable {
+            source: source,
+            initial: initial,
+            f: f,
+            push_initial: false,
+        }
+    }
+
+    pub fn new_seeded(source: &'a mut Source, initial: A, f: F) -> ScanObservable<'a, Source, A, F> {
+        ScanObservable {
+            source: source,
+            initial: initial,
+            f: f,
+            push_initial: true,
+        }
+    }
+}
+
+impl<'a, Source, A, F> Observable for ScanObservable<'a, Source, A, F>
+where Source: Observable,
+      A: Clone,
+      F: Fn(A, <Source as Observable>::Item) -> A {
+    type Item = A;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        if self.push_initial {
+            observer.on_next(self.initial.clone());
+        }
+
+        // Every observer starts accumulating from its own copy of the initial
+        // value, so subscriptions do not influence one another.
+        let scan_observer = ScanObserver {
+            observer: observer,
+            accumulator: self.initial.clone(),
+            f: &self.f,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(scan_observer)
+    }
+}
+
+struct ReduceObserver<T, E, A, U, O, F, G>
+where O: Observer<U, E>,
+      F: Fn(A, T) -> A,
+      G: Fn(A) -> Option<U> {
+    observer: O,
+    // This is only `None` while f is being called.
+    accumulator: Option<A>,
+    f: F,
+    finish: G,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, A, U, O, F, G> Observer<T, E> for ReduceObserver<T, E, A, U, O, F, G>
+where T: Clone,
+      E: Clone,
+      U: Clone,
+      O: Observer<U, E>,
+      F: Fn(A, T) -> A,
+      G: Fn(A) -> Option<U> {
+    fn on_next(&mut self, item: T) {
+        let accumulator = self.accumulator.take().unwrap();
+        self.accumulator = Some(self.f.call((accumulator, item)));
+    }
+
+    fn on_completed(mut self) {
+        if let Some(result) = self.finish.call((self.accumulator.unwrap(),)) {
+            self.observer.on_next(result);
+        }
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        // The accumulator is incomplete, so it is not pushed.
+        self.observer.on_error(error);
+    }
+}
+
+/// An observable that aggregates all values of the source into one value.
+///
+/// The accumulator is updated with f for every value. When the source
+/// completes, `finish` determines what to push, if anything. This is the basis
+/// of `fold()`, `min()`, `max()`, and `sum()`.
+pub struct ReduceObservable<'a, Source: 'a + ?Sized, A, F, G> {
+    source: &'a mut Source,
+    initial: A,
+    f: F,
+    finish: G,
+}
+
+impl<'a, Source: 'a + ?Sized, A, F, G> ReduceObservable<'a, Source, A, F, G> {
+    pub fn new(source: &'a mut Source, initial: A, f: F, finish: G) -> ReduceObservable<'a, Source, A, F, G> {
+        ReduceObservable {
+            source: source,
+            initial: initial,
+            f: f,
+            finish: finish,
+        }
+    }
+}
+
+impl<'a, Source, A, U, F, G> Observable for ReduceObservable<'a, Source, A, F, G>
+where Source: Observable,
+      A: Clone,
+      U: Clone,
+      F: Fn(A, <Source as Observable>::Item) -> A,
+      G: Fn(A) -> Option<U> {
+    type Item = U;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let reduce_observer = ReduceObserver {
+            observer: observer,
+            accumulator: Some(self.initial.clone()),
+            f: &self.f,
+            finish: &self.finish,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(reduce_observer)
+    }
+}
+
+/// The result of calling `fold()` on an observable.
+pub type FoldObservable<'a, Source, A, F> = ReduceObservable<'a, Source, A, F, fn(A) -> Option<A>>;
+
+/// The result of calling `min()` on an observable.
+pub type MinObservable<'a, Source> = ReduceObservable<'a, Source,
+    Option<<Source as Observable>::Item>,
+    fn(Option<<Source as Observable>::Item>, <Source as Observable>::Item) -> Option<<Source as Observable>::Item>,
+    fn(Option<<Source as Observable>::Item>) -> Option<<Source as Observable>::Item>>;
+
+/// The result of calling `max()` on an observable.
+pub type MaxObservable<'a, Source> = MinObservable<'a, Source>;
+
+/// The result of calling `sum()` on an observable.
+pub type SumObservable<'a, Source> = ReduceObservable<'a, Source,
+    <Source as Observable>::Item,
+    fn(<Source as Observable>::Item, <Source as Observable>::Item) -> <Source as Observable>::Item,
+    fn(<Source as Observable>::Item) -> Option<<Source as Observable>::Item>>;
+
+/// Returns the smaller of the current minimum (if any) and x.
+pub(crate) fn min_step<T: PartialOrd>(min: Option<T>, x: T) -> Option<T> {
+    match min {
+        Some(m) => if x < m { Some(x) } else { Some(m) },
+        None => Some(x),
+    }
+}
+
+/// Returns the larger of the current maximum (if any) and x.
+pub(crate) fn max_step<T: PartialOrd>(max: Option<T>, x: T) -> Option<T> {
+    match max {
+        Some(m) => if x > m { Some(x) } else { Some(m) },
+        None => Some(x),
+    }
+}
+
+/// Returns the sum of the accumulator and x.
+pub(crate) fn sum_step<T: Add<Output = T>>(sum: T, x: T) -> T {
+    sum + x
+}
+
+/// Returns its argument, for use as `ReduceObservable` finish function.
+pub(crate) fn identity<T>(x: T) -> T {
+    x
+}
+
+struct LastObserver<T, E, O>
+where O: Observer<T, E> {
+    observer: O,
+    last: Option<T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for LastObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        self.last = Some(item);
+    }
+
+    fn on_completed(mut self) {
+        if let Some(item) = self.last {
+            self.observer.on_next(item);
+        }
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `last()` on an observable.
+pub struct LastObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> LastObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> LastObservable<'a, Source> {
+        LastObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for LastObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let last_observer = LastObserver {
+            observer: observer,
+            last: None,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(last_observer)
+    }
+}
+
+/// The subscription to an observable that may stop before the source terminates.
+pub struct ShortCircuitSubscription<Source: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: lifeline::Lifeline<Option<Source::Subscription>>,
+}
+
+impl<Source: Observable> Subscription for ShortCircuitSubscription<Source> { }
+
+impl<Source: Observable> ShortCircuitSubscription<Source> {
+    /// Subscribes the observer returned by `make_observer` to the source.
+    ///
+    /// The observer receives an unsubscriber that it can use to drop the
+    /// subscription to the source.
+    fn subscribe<O, F>(source: &mut Source, make_observer: F) -> ShortCircuitSubscription<Source>
+        where O: Observer<Source::Item, Source::Error>,
+              F: FnOnce(Unsubscriber<Source::Subscription>) -> O {
+        let (mut life, owner) = lifeline::new(None);
+        let observer = make_observer(Unsubscriber { owner: Some(owner) });
+        let subs_source = source.subscribe(observer);
+
+        // If the observer unsubscribed already, the owner took the value out
+        // of the lifeline, and the closure is not called. Then the source
+        // subscription is dropped here.
+        life.with_mut_value(move |subs| *subs = Some(subs_source));
+        ShortCircuitSubscription {
+            subs_source: life,
+        }
+    }
+}
+
+/// Allows an observer to drop its own subscription to the source.
+struct Unsubscriber<S> {
+    owner: Option<lifeline::Owner<Option<S>>>,
+}
+
+impl<S> Unsubscriber<S> {
+    /// Drops the subscription to the source, so it stops pushing values.
+    ///
+    /// Taking the subscription out of the lifeline drops it. If this is called
+    /// during subscription, the subscription is dropped as soon as it is
+    /// stored in the lifeline.
+    fn unsubscribe(&mut self) {
+        if let Some(owner) = self.owner.take() {
+            drop(owner.take());
+        }
+    }
+}
+
+struct FirstObserver<T, E, O, S>
+where O: Observer<T, E> {
+    observer: Option<O>,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> Observer<T, E> for FirstObserver<T, E, O, S>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if let Some(mut observer) = self.observer.take() {
+            observer.on_next(item);
+            observer.on_completed();
+            self.unsubscriber.unsubscribe();
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.observer {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `first()` on an observable.
+pub struct FirstObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> FirstObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> FirstObservable<'a, Source> {
+        FirstObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for FirstObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ShortCircuitSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        ShortCircuitSubscription::subscribe(self.source, |unsubscriber| FirstObserver {
+            observer: Some(observer),
+            unsubscriber: unsubscriber,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        })
+    }
+}
+
+struct ElementAtObserver<T, E, O, S>
+where O: Observer<T, E> {
+    observer: Option<O>,
+    // The number of values to skip before the value to push.
+    remaining: usize,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> Observer<T, E> for ElementAtObserver<T, E, O, S>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            return
+        }
+        if let Some(mut observer) = self.observer.take() {
+            observer.on_next(item);
+            observer.on_completed();
+            self.unsubscriber.unsubscribe();
+        }
+    }
+
+    fn on_completed(self) {
+        // If the source completes before the index was reached, there is no
+        // value to push. This mirrors `first()` on an empty observable.
+        if let Some(observer) = self.observer {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `element_at()` on an observable.
+pub struct ElementAtObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    index: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> ElementAtObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, index: usize) -> ElementAtObservable<'a, Source> {
+        ElementAtObservable {
+            source: source,
+            index: index,
+        }
+    }
+}
+
+impl<'a, Source> Observable for ElementAtObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ShortCircuitSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let index = self.index;
+        ShortCircuitSubscription::subscribe(self.source, |unsubscriber| ElementAtObserver {
+            observer: Some(observer),
+            remaining: index,
+            unsubscriber: unsubscriber,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        })
+    }
+}
+
+/// The reason why `single()` failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SingleError {
+    /// The observable completed without producing a value.
+    Empty,
+    /// The observable produced more than one value.
+    Multiple,
+}
+
+/// Allows `single()` on observables that fail with `()`, such as slices.
+impl From<SingleError> for () {
+    fn from(_error: SingleError) {}
+}
+
+struct SingleObserver<T, E, O, S>
+where O: Observer<T, E> {
+    observer: Option<O>,
+    value: Option<T>,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> Observer<T, E> for SingleObserver<T, E, O, S>
+where T: Clone,
+      E: Clone + From<SingleError>,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if self.observer.is_none() {
+            return;
+        }
+        if self.value.is_none() {
+            self.value = Some(item);
+            return;
+        }
+        if let Some(observer) = self.observer.take() {
+            observer.on_error(E::from(SingleError::Multiple));
+        }
+        self.unsubscriber.unsubscribe();
+    }
+
+    fn on_completed(self) {
+        if let Some(mut observer) = self.observer {
+            match self.value {
+                Some(item) => {
+                    observer.on_next(item);
+                    observer.on_completed();
+                }
+                None => observer.on_error(E::from(SingleError::Empty)),
+            }
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `single()` on an observable.
+pub struct SingleObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> SingleObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> SingleObservable<'a, Source> {
+        SingleObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for SingleObservable<'a, Source>
+where Source: Observable,
+      <Source as Observable>::Error: From<SingleError> {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ShortCircuitSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        ShortCircuitSubscription::subscribe(self.source, |unsubscriber| SingleObserver {
+            observer: Some(observer),
+            value: None,
+            unsubscriber: unsubscriber,
+            _phantom_e: PhantomData,
+        })
+    }
+}
+
+/// The subscription to an observable that subscribes to two observables.
+pub struct PairSubscription<Source: Observable, ObOther: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: Source::Subscription,
+
+    #[allow(dead_code)] // Same here.
+    subs_other: ObOther::Subscription,
+}
+
+impl<Source: Observable, ObOther: Observable> Subscription for PairSubscription<Source, ObOther> { }
+
+/// State shared by the observers of the sources of a merged observable.
+struct MergeState<O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// The number of sources that have not completed yet.
+    active: usize,
+}
+
+struct MergeObserver<T, E, O>
+where O: Observer<T, E> {
+    // Note that the state cannot be kept in a lifeline, because the
+    // subscription would have to own the lifeline, and the type of the
+    // subscription cannot depend on the type of the observer.
+    state: Rc<RefCell<MergeState<O>>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for MergeObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.active -= 1;
+        if state.active == 0 {
+            if let Some(observer) = state.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn on_error(self, error: E) {
+        // Taking the observer ensures that values and completion of the other
+        // sources are not pushed after the error.
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `merge()` on an observable.
+pub struct MergeObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> {
+    source: &'a mut Source,
+    other: &'a mut ObOther,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> MergeObservable<'a, Source, ObOther> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther) -> MergeObservable<'a, Source, ObOther> {
+        MergeObservable {
+            source: source,
+            other: other,
+        }
+    }
+}
+
+impl<'a, T: Clone, E: Clone, Source, ObOther> Observable for MergeObservable<'a, Source, ObOther>
+where Source: Observable<Item = T, Error = E>,
+      ObOther: Observable<Item = T, Error = E> {
+    type Item = T;
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(MergeState {
+            observer: Some(observer),
+            active: 2,
+        }));
+        let source_observer = MergeObserver {
+            state: state.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let other_observer = MergeObserver {
+            state: state,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let subs_source = self.source.subscribe(source_observer);
+        let subs_other = self.other.subscribe(other_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+pub struct ConcatSubscription<Ob: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscriptions alive.
+    subscriptions: lifeline::Lifeline<Vec<Ob::Subscription>>,
+}
+
+impl<Ob: Observable> Subscription for ConcatSubscription<Ob> { }
+
+struct ConcatObserver<'a, Ob: 'a, O>
+where Ob: Observable,
+      O: Observer<Ob::Item, Ob::Error> {
+    observer: O,
+    rest: &'a mut [Ob],
+    subscriptions: lifeline::Owner<Vec<Ob::Subscription>>,
+}
+
+impl<'a, Ob, O> Observer<Ob::Item, Ob::Error> for ConcatObserver<'a, Ob, O>
+where Ob: Observable,
+      O: Observer<Ob::Item, Ob::Error> {
+    fn on_next(&mut self, item: Ob::Item) {
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        let ConcatObserver { observer, rest, mut subscriptions } = self;
+        match rest.split_first_mut() {
+            None => observer.on_completed(),
+            Some((next, rest)) => {
+                let next_observer = ConcatObserver {
+                    observer: observer,
+                    rest: rest,
+                    subscriptions: subscriptions.clone(),
+                };
+                let subs_next = next.subscribe(next_observer);
+
+                // If the next observable completes during subscription, the
+                // subscription of the one after it is pushed first. The order
+                // does not matter, all subscriptions are kept alive until the
+                // concatenated subscription is dropped.
+                subscriptions.with_mut_value(move |subs| subs.push(subs_next));
+            }
+        }
+    }
+
+    fn on_error(self, error: Ob::Error) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `concat()` on a slice of observables.
+pub struct ConcatObservable<'a, Ob: 'a> {
+    sources: &'a mut [Ob],
+}
+
+impl<'a, Ob: 'a> ConcatObservable<'a, Ob> {
+    pub fn new(sources: &'a mut [Ob]) -> ConcatObservable<'a, Ob> {
+        ConcatObservable {
+            sources: sources,
+        }
+    }
+}
+
+impl<'a, Ob> Observable for ConcatObservable<'a, Ob>
+where Ob: Observable {
+    type Item = <Ob as Observable>::Item;
+    type Error = <Ob as Observable>::Error;
+    type Subscription = ConcatSubscription<Ob>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (mut life, owner) = lifeline::new(Vec::new());
+        match self.sources.split_first_mut() {
+            None => observer.on_completed(),
+            Some((first, rest)) => {
+                let concat_observer = ConcatObserver {
+                    observer: observer,
+                    rest: rest,
+                    subscriptions: owner,
+                };
+                let subs_first = first.subscribe(concat_observer);
+                life.with_mut_value(move |subs| subs.push(subs_first));
+            }
+        }
+        ConcatSubscription {
+            subscriptions: life,
+        }
+    }
+}
+
+/// Joins a sequence of observables sequentially.
+///
+/// An observer receives the values of the first observable until it completes,
+/// then the values of the second one, and so on. An observable is only
+/// subscribed to after the previous one completed. The concatenated observable
+/// completes after the last one completes, or immediately if there are no
+/// observables. If any of the observables fails, the error is passed through
+/// and the remaining observables are not subscribed to.
+///
+/// See also `Observable::continue_with()`.
+pub fn concat<'s, Ob>(sources: &'s mut [Ob]) -> ConcatObservable<'s, Ob>
+    where Ob: Observable {
+    ConcatObservable::new(sources)
+}
+
+/// The subscription to the observables merged by `merge_all()` or `merge_all_delay_error()`.
+pub struct MergeAllSubscription<Ob: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscriptions alive.
+    subscriptions: lifeline::Lifeline<Vec<Ob::Subscription>>,
+}
+
+impl<Ob: Observable> Subscription for MergeAllSubscription<Ob> { }
+
+struct MergeAllObserver<T, E, O, S>
+where O: Observer<T, E> {
+    state: Rc<RefCell<MergeState<O>>>,
+    subscriptions: lifeline::Owner<Vec<S>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> Observer<T, E> for MergeAllObserver<T, E, O, S>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.active -= 1;
+        if state.active == 0 {
+            if let Some(observer) = state.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn on_error(self, error: E) {
+        let observer = self.state.borrow_mut().observer.take();
+        if let Some(observer) = observer {
+            observer.on_error(error);
+        }
+        // Drop the subscriptions to the other sources. The state must not be
+        // borrowed here, because dropping them drops their observers too.
+        drop(self.subscriptions.take());
+    }
+}
+
+/// The result of calling `merge_all()` on a slice of observables.
+pub struct MergeAllObservable<'a, Ob: 'a> {
+    sources: &'a mut [Ob],
+}
+
+impl<'a, Ob: 'a> MergeAllObservable<'a, Ob> {
+    pub fn new(sources: &'a mut [Ob]) -> MergeAllObservable<'a, Ob> {
+        MergeAllObservable {
+            sources: sources,
+        }
+    }
+}
+
+impl<'a, Ob> Observable for MergeAllObservable<'a, Ob>
+where Ob: Observable {
+    type Item = <Ob as Observable>::Item;
+    type Error = <Ob as Observable>::Error;
+    type Subscription = MergeAllSubscription<Ob>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (mut life, _) = lifeline::new(Vec::new());
+        if self.sources.is_empty() {
+            observer.on_completed();
+        } else {
+            let state = Rc::new(RefCell::new(MergeState {
+                observer: Some(observer),
+                active: self.sources.len(),
+            }));
+            for source in self.sources.iter_mut() {
+                // Stop subscribing if one of the sources failed already.
+                if state.borrow().observer.is_none() {
+                    break;
+                }
+                let merge_observer = MergeAllObserver {
+                    state: state.clone(),
+                    subscriptions: life.owner(),
+                    _phantom_t: PhantomData,
+                    _phantom_e: PhantomData,
+                };
+                let subscription = source.subscribe(merge_observer);
+
+                // If a source failed during subscription, the subscriptions
+                // have been dropped, and this one is dropped immediately.
+                life.with_mut_value(move |subs| subs.push(subscription));
+            }
+        }
+        MergeAllSubscription {
+            subscriptions: life,
+        }
+    }
+}
+
+/// Merges a sequence of observables into one.
+///
+/// All observables are subscribed to at once, and an observer receives the
+/// values of all of them as they are pushed. The merged observable completes
+/// after every observable completed, or immediately if there are no
+/// observables. If any of the observables fails, the error is passed through
+/// and the subscriptions to the other observables are dropped.
+///
+/// See also `Observable::merge()` and `concat()`.
+pub fn merge_all<'s, Ob>(sources: &'s mut [Ob]) -> MergeAllObservable<'s, Ob>
+    where Ob: Observable {
+    MergeAllObservable::new(sources)
+}
+
+/// State shared by the observers of the sources of `merge_all_delay_error()`.
+struct MergeDelayErrorState<E, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// The number of sources that have not terminated yet.
+    active: usize,
+
+    /// The first error of a source, passed on after all sources terminated.
+    error: Option<E>,
+}
+
+impl<E, O> MergeDelayErrorState<E, O> {
+    /// Marks a source terminated, and terminates the observer after the last one.
+    fn terminate<T>(&mut self) where O: Observer<T, E> {
+        self.active -= 1;
+        if self.active == 0 {
+            if let Some(observer) = self.observer.take() {
+                match self.error.take() {
+                    Some(error) => observer.on_error(error),
+                    None => observer.on_completed(),
+                }
+            }
+        }
+    }
+}
+
+struct MergeDelayErrorObserver<T, E, O>
+where O: Observer<T, E> {
+    state: Rc<RefCell<MergeDelayErrorState<E, O>>>,
+    _phantom_t: PhantomData<*mut T>,
+}
+
+impl<T, E, O> Observer<T, E> for MergeDelayErrorObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        self.state.borrow_mut().terminate();
+    }
+
+    fn on_error(self, error: E) {
+        let mut state = self.state.borrow_mut();
+        if state.error.is_none() {
+            state.error = Some(error);
+        }
+        state.terminate();
+    }
+}
+
+/// The result of calling `merge_all_delay_error()` on a slice of observables.
+pub struct MergeAllDelayErrorObservable<'a, Ob: 'a> {
+    sources: &'a mut [Ob],
+}
+
+impl<'a, Ob: 'a> MergeAllDelayErrorObservable<'a, Ob> {
+    pub fn new(sources: &'a mut [Ob]) -> MergeAllDelayErrorObservable<'a, Ob> {
+        MergeAllDelayErrorObservable {
+            sources: sources,
+        }
+    }
+}
+
+impl<'a, Ob> Observable for MergeAllDelayErrorObservable<'a, Ob>
+where Ob: Observable {
+    type Item = <Ob as Observable>::Item;
+    type Error = <Ob as Observable>::Error;
+    type Subscription = MergeAllSubscription<Ob>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (mut life, _) = lifeline::new(Vec::new());
+        if self.sources.is_empty() {
+            observer.on_completed();
+        } else {
+            let state = Rc::new(RefCell::new(MergeDelayErrorState {
+                observer: Some(observer),
+                active: self.sources.len(),
+                error: None,
+            }));
+            for source in self.sources.iter_mut() {
+                let merge_observer = MergeDelayErrorObserver {
+                    state: state.clone(),
+                    _phantom_t: PhantomData,
+                };
+                let subscription = source.subscribe(merge_observer);
+                life.with_mut_value(move |subs| subs.push(subscription));
+            }
+        }
+        MergeAllSubscription {
+            subscriptions: life,
+        }
+    }
+}
+
+/// Merges a sequence of observables into one, passing on errors at the end.
+///
+/// Like `merge_all()`, but when an observable fails, the other observables
+/// keep pushing values. After every observable completed or failed, the
+/// observer receives the error of the first observable that failed, or
+/// completes if none of them failed.
+pub fn merge_all_delay_error<'s, Ob>(sources: &'s mut [Ob]) -> MergeAllDelayErrorObservable<'s, Ob>
+    where Ob: Observable {
+    MergeAllDelayErrorObservable::new(sources)
+}
+
+/// The result of calling `start_with()` on an observable.
+pub struct StartWithObservable<'a, Source: 'a + ?Sized + Observable> {
+    source: &'a mut Source,
+    values: Vec<Source::Item>,
+}
+
+impl<'a, Source: 'a + ?Sized + Observable> StartWithObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, values: Vec<Source::Item>) -> StartWithObservable<'a, Source> {
+        StartWithObservable {
+            source: source,
+            values: values,
+        }
+    }
+}
+
+impl<'a, Source> Observable for StartWithObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        for value in &self.values {
+            observer.on_next(value.clone());
+        }
+        self.source.subscribe(observer)
+    }
+}
+
+pub struct CatchSubscription<Source: Observable, ObAlt: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: Source::Subscription,
+
+    #[allow(dead_code)] // Same here.
+    subs_alt: lifeline::Lifeline<Option<ObAlt::Subscription>>,
+}
+
+impl<Source: Observable, ObAlt: Observable> Subscription for CatchSubscription<Source, ObAlt> { }
+
+struct CatchObserver<T: Clone, E: Clone, ObAlt, O, F>
+where ObAlt: Observable<Item = T, Error = E>,
+      O: Observer<T, E>,
+      F: Fn(E) -> ObAlt {
+    observer: O,
+    handler: F,
+    subscription: lifeline::Owner<Option<ObAlt::Subscription>>,
+}
+
+impl<T, E, ObAlt, O, F> Observer<T, E> for CatchObserver<T, E, ObAlt, O, F>
+where T: Clone,
+      E: Clone,
+      ObAlt: Observable<Item = T, Error = E>,
+      O: Observer<T, E>,
+      F: Fn(E) -> ObAlt {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        let CatchObserver { observer, handler, mut subscription } = self;
+        let mut alternative = handler.call((error,));
+        let subs_alt = alternative.subscribe(observer);
+        subscription.with_mut_value(move |subs| *subs = Some(subs_alt));
+    }
+}
+
+/// The result of calling `catch_error()` on an observable.
+pub struct CatchObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    handler: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> CatchObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, handler: F) -> CatchObservable<'a, Source, F> {
+        CatchObservable {
+            source: source,
+            handler: handler,
+        }
+    }
+}
+
+impl<'a, T: Clone, E: Clone, Source, ObAlt, F> Observable for CatchObservable<'a, Source, F>
+where Source: Observable<Item = T, Error = E>,
+      ObAlt: Observable<Item = T, Error = E>,
+      F: Fn(E) -> ObAlt {
+    type Item = T;
+    type Error = E;
+    type Subscription = CatchSubscription<Source, ObAlt>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (life, owner) = lifeline::new(None);
+        let catch_observer = CatchObserver {
+            observer: observer,
+            handler: &self.handler,
+            subscription: owner,
+        };
+        let subs_source = self.source.subscribe(catch_observer);
+        CatchSubscription {
+            subs_source: subs_source,
+            subs_alt: life,
+        }
+    }
+}
+
+/// The subscription to the current round of an observable that is subscribed to repeatedly.
+type ResubscribeSubscription<Source> = lifeline::Lifeline<Option<<Source as Observable>::Subscription>>;
+
+/// State shared by the observers of the rounds of an observable that is subscribed to repeatedly.
+///
+/// This is used by `retry()`, `repeat()`, `retry_when()` and `repeat_when()`.
+struct ResubscribeState<'a, Source: 'a + ?Sized, O, C> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// The observable, `None` while it is being subscribed to.
+    source: Option<&'a mut Source>,
+
+    /// What decides whether another round follows, specific to the operator.
+    ///
+    /// For `retry()` and `repeat()` this is the number of times that the
+    /// observable may be subscribed to again.
+    control: C,
+
+    /// Whether a round ended while the observable was being subscribed to.
+    resubscribe: bool,
+}
+
+/// Subscribes the observer returned by `make_observer` for a new round.
+///
+/// If the round ends while the observable is being subscribed to, the next
+/// round starts after the current call to `subscribe()` returns, so rounds
+/// never nest. The subscription to the previous round is dropped when the
+/// subscription to the new round is stored. The state must not be borrowed
+/// when this is called.
+fn resubscribe<'a, Source: 'a + ?Sized, O, C, R, F>(state: &Rc<RefCell<ResubscribeState<'a, Source, O, C>>>,
+                                                     subs_source: &lifeline::Owner<Option<Source::Subscription>>,
+                                                     make_observer: F)
+    where Source: Observable,
+          R: Observer<Source::Item, Source::Error>,
+          F: Fn() -> R {
+    loop {
+        let source = state.borrow_mut().source.take();
+        let source = match source {
+            Some(source) => source,
+            None => {
+                state.borrow_mut().resubscribe = true;
+                return;
+            }
+        };
+        let subscription = source.subscribe(make_observer());
+        subs_source.clone().with_mut_value(move |subs| *subs = Some(subscription));
+
+        let mut state = state.borrow_mut();
+        state.source = Some(source);
+        if !state.resubscribe {
+            return;
+        }
+        state.resubscribe = false;
+    }
+}
+
+pub struct RetrySubscription<Source: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: ResubscribeSubscription<Source>,
+}
+
+impl<Source: Observable> Subscription for RetrySubscription<Source> { }
+
+struct RetryObserver<'a, Source: 'a + ?Sized, O>
+where Source: Observable {
+    state: Rc<RefCell<ResubscribeState<'a, Source, O, usize>>>,
+    subs_source: lifeline::Owner<Option<Source::Subscription>>,
+}
+
+impl<'a, Source: 'a + ?Sized, O> RetryObserver<'a, Source, O>
+where Source: Observable,
+      O: Observer<Source::Item, Source::Error> {
+    /// Subscribes to the observable for a new attempt.
+    fn subscribe(state: &Rc<RefCell<ResubscribeState<'a, Source, O, usize>>>,
+                 subs_source: &lifeline::Owner<Option<Source::Subscription>>) {
+        resubscribe(state, subs_source, || RetryObserver {
+            state: state.clone(),
+            subs_source: subs_source.clone(),
+        });
+    }
+}
+
+impl<'a, Source: 'a + ?Sized, O> Observer<Source::Item, Source::Error> for RetryObserver<'a, Source, O>
+where Source: Observable,
+      O: Observer<Source::Item, Source::Error> {
+    fn on_next(&mut self, item: Source::Item) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: Source::Error) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.control == 0 {
+                if let Some(observer) = state.observer.take() {
+                    observer.on_error(error);
+                }
+                return;
+            }
+            state.control -= 1;
+        }
+        RetryObserver::subscribe(&self.state, &self.subs_source);
+    }
+}
+
+/// The result of calling `retry()` on an observable.
+pub struct RetryObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    max_attempts: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> RetryObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, max_attempts: usize) -> RetryObservable<'a, Source> {
+        RetryObservable {
+            source: source,
+            max_attempts: max_attempts,
+        }
+    }
+}
+
+impl<'a, Source> Observable for RetryObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = RetrySubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(ResubscribeState {
+            observer: Some(observer),
+            source: Some(&mut *self.source),
+            control: self.max_attempts,
+            resubscribe: false,
+        }));
+        let (life, owner) = lifeline::new(None);
+        RetryObserver::subscribe(&state, &owner);
+        RetrySubscription {
+            subs_source: life,
+        }
+    }
+}
+
+pub struct RetryWhenSubscription<Source: Observable, ObNotifier: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: ResubscribeSubscription<Source>,
+
+    #[allow(dead_code)] // Same here.
+    subs_notifier: lifeline::Lifeline<Option<ObNotifier::Subscription>>,
+}
+
+impl<Source, ObNotifier> Subscription for RetryWhenSubscription<Source, ObNotifier>
+where Source: Observable,
+      ObNotifier: Observable { }
+
+/// State shared by the observers of a retry-when observable.
+///
+/// The control is the function that turns an error into a notifier.
+type RetryWhenState<'a, Source, F, O> = Rc<RefCell<ResubscribeState<'a, Source, O, &'a mut F>>>;
+
+struct RetryWhenObserver<'a, Source: 'a + ?Sized, ObNotifier, F: 'a, O>
+where Source: Observable,
+      ObNotifier: Observable {
+    state: RetryWhenState<'a, Source, F, O>,
+    subs_source: lifeline::Owner<Option<Source::Subscription>>,
+    subs_notifier: lifeline::Owner<Option<ObNotifier::Subscription>>,
+}
+
+impl<'a, Source: 'a + ?Sized, ObNotifier, F, O> RetryWhenObserver<'a, Source, ObNotifier, F, O>
+where Source: Observable,
+      ObNotifier: Observable<Error = <Source as Observable>::Error>,
+      F: FnMut(<Source as Observable>::Error) -> ObNotifier,
+      O: Observer<Source::Item, Source::Error> {
+    /// Subscribes to the observable for a new attempt.
+    fn subscribe(state: &RetryWhenState<'a, Source, F, O>,
+                 subs_source: &lifeline::Owner<Option<Source::Subscription>>,
+                 subs_notifier: &lifeline::Owner<Option<ObNotifier::Subscription>>) {
+        resubscribe(state, subs_source, || RetryWhenObserver {
+            state: state.clone(),
+            subs_source: subs_source.clone(),
+            subs_notifier: subs_notifier.clone(),
+        });
+    }
+}
+
+impl<'a, Source: 'a + ?Sized, ObNotifier, F, O> Observer<Source::Item, Source::Error> for RetryWhenObserver<'a, Source, ObNotifier, F, O>
+where Source: Observable,
+      ObNotifier: Observable<Error = <Source as Observable>::Error>,
+      F: FnMut(<Source as Observable>::Error) -> ObNotifier,
+      O: Observer<Source::Item, Source::Error> {
+    fn on_next(&mut self, item: Source::Item) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: Source::Error) {
+        let mut notifier = {
+            let mut state = self.state.borrow_mut();
+            if state.observer.is_none() {
+                return;
+            }
+            state.control.call_mut((error,))
+        };
+        let notifier_observer = RetryWhenNotifierObserver {
+            state: self.state,
+            subs_source: self.subs_source,
+            subs_notifier: self.subs_notifier.clone(),
+            fired: false,
+        };
+        let subs = notifier.subscribe(notifier_observer);
+        let mut subs_notifier = self.subs_notifier;
+        subs_notifier.with_mut_value(move |subs_old| *subs_old = Some(subs));
+    }
+}
+
+struct RetryWhenNotifierObserver<'a, Source: 'a + ?Sized, ObNotifier, F: 'a, O>
+where Source: Observable,
+      ObNotifier: Observable {
+    state: RetryWhenState<'a, Source, F, O>,
+    subs_source: lifeline::Owner<Option<Source::Subscription>>,
+    subs_notifier: lifeline::Owner<Option<ObNotifier::Subscription>>,
+
+    /// Whether the notifier pushed a value already; later events are ignored.
+    fired: bool,
+}
+
+impl<'a, Source: 'a + ?Sized, ObNotifier, F, O> Observer<ObNotifier::Item, Source::Error> for RetryWhenNotifierObserver<'a, Source, ObNotifier, F, O>
+where Source: Observable,
+      ObNotifier: Observable<Error = <Source as Observable>::Error>,
+      F: FnMut(<Source as Observable>::Error) -> ObNotifier,
+      O: Observer<Source::Item, Source::Error> {
+    fn on_next(&mut self, _item: ObNotifier::Item) {
+        if self.fired || self.state.borrow().observer.is_none() {
+            return;
+        }
+        self.fired = true;
+        RetryWhenObserver::subscribe(&self.state, &self.subs_source, &self.subs_notifier);
+    }
+
+    fn on_completed(self) {
+        if self.fired {
+            return;
+        }
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: Source::Error) {
+        if self.fired {
+            return;
+        }
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `retry_when()` on an observable.
+pub struct RetryWhenObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    handler: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> RetryWhenObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, handler: F) -> RetryWhenObservable<'a, Source, F> {
+        RetryWhenObservable {
+            source: source,
+            handler: handler,
+        }
+    }
+}
+
+impl<'a, Source, ObNotifier, F> Observable for RetryWhenObservable<'a, Source, F>
+where Source: Observable,
+      ObNotifier: Observable<Error = <Source as Observable>::Error>,
+      F: FnMut(<Source as Observable>::Error) -> ObNotifier {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = RetryWhenSubscription<Source, ObNotifier>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(ResubscribeState {
+            observer: Some(observer),
+            source: Some(&mut *self.source),
+            control: &mut self.handler,
+            resubscribe: false,
+        }));
+        let (life_source, owner_source) = lifeline::new(None);
+        let (life_notifier, owner_notifier) = lifeline::new(None);
+        RetryWhenObserver::subscribe(&state, &owner_source, &owner_notifier);
+        RetryWhenSubscription {
+            subs_source: life_source,
+            subs_notifier: life_notifier,
+        }
+    }
+}
+
+struct InspectObserver<T, E, O, F>
+where O: Observer<T, E>,
+      F: Fn(&T) {
+    observer: O,
+    f: F,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, F> Observer<T, E> for InspectObserver<T, E, O, F>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      F: Fn(&T) {
+    fn on_next(&mut self, item: T) {
+        self.f.call((&item,));
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `inspect()` on an observable.
+pub struct InspectObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> InspectObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> InspectObservable<'a, Source, F> {
+        InspectObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for InspectObservable<'a, Source, F>
+where Source: Observable,
+      F: Fn(&<Source as Observable>::Item) {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let inspect_observer = InspectObserver {
+            observer: observer,
+            f: &self.f,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(inspect_observer)
+    }
+}
+
+struct InspectCompletedObserver<T, E, O, F>
+where O: Observer<T, E>,
+      F: Fn() {
+    observer: O,
+    f: F,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, F> Observer<T, E> for InspectCompletedObserver<T, E, O, F>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      F: Fn() {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.f.call(());
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `inspect_completed()` on an observable.
+pub struct InspectCompletedObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> InspectCompletedObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> InspectCompletedObservable<'a, Source, F> {
+        InspectCompletedObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for InspectCompletedObservable<'a, Source, F>
+where Source: Observable,
+      F: Fn() {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let inspect_observer = InspectCompletedObserver {
+            observer: observer,
+            f: &self.f,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(inspect_observer)
+    }
+}
+
+struct InspectErrorObserver<T, E, O, F>
+where O: Observer<T, E>,
+      F: Fn(&E) {
+    observer: O,
+    f: F,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, F> Observer<T, E> for InspectErrorObserver<T, E, O, F>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      F: Fn(&E) {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.f.call((&error,));
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `inspect_error()` on an observable.
+pub struct InspectErrorObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> InspectErrorObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> InspectErrorObservable<'a, Source, F> {
+        InspectErrorObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for InspectErrorObservable<'a, Source, F>
+where Source: Observable,
+      F: Fn(&<Source as Observable>::Error) {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let inspect_observer = InspectErrorObserver {
+            observer: observer,
+            f: &self.f,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(inspect_observer)
+    }
+}
+
+struct CheckedObserver<T, E, O>
+where O: Observer<T, E> {
+    /// The downstream observer, `None` after it completed or failed.
+    ///
+    /// The terminal methods consume the observer, so in safe code this is
+    /// never `None` when a method is called. But an observable that keeps a
+    /// duplicate of the observer shares this state with the duplicate.
+    observer: Rc<RefCell<Option<O>>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Clone for CheckedObserver<T, E, O>
+where O: Observer<T, E> {
+    fn clone(&self) -> CheckedObserver<T, E, O> {
+        CheckedObserver {
+            observer: self.observer.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        }
+    }
+}
+
+impl<T, E, O> CheckedObserver<T, E, O>
+where O: Observer<T, E> {
+    /// Takes the observer out, panicking if it terminated already.
+    fn take_observer(&self, call: &str) -> Option<O> {
+        let observer = self.observer.borrow_mut().take();
+        debug_assert!(observer.is_some(),
+                      "observable contract violated: {} called after termination", call);
+        observer
+    }
+}
+
+impl<T, E, O> Observer<T, E> for CheckedObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        let terminated = self.observer.borrow().is_none();
+        debug_assert!(!terminated,
+                      "observable contract violated: on_next called after termination");
+        if let Some(ref mut observer) = *self.observer.borrow_mut() {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.take_observer("on_completed") {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.take_observer("on_error") {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `checked()` on an observable.
+pub struct CheckedObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> CheckedObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> CheckedObservable<'a, Source> {
+        CheckedObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for CheckedObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let checked_observer = CheckedObserver {
+            observer: Rc::new(RefCell::new(Some(observer))),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+
+        // Keep the shared state alive while subscribing, so a source that
+        // duplicated the observer can still be caught after the original
+        // terminated and was dropped.
+        let _state = checked_observer.clone();
+        self.source.subscribe(checked_observer)
+    }
+}
+
+/// The result of calling `instrument_subscribe()` on an observable.
+pub struct InstrumentObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    on_subscribe: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> InstrumentObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, on_subscribe: F) -> InstrumentObservable<'a, Source, F> {
+        InstrumentObservable {
+            source: source,
+            on_subscribe: on_subscribe,
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for InstrumentObservable<'a, Source, F>
+where Source: Observable,
+      F: FnMut() {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.on_subscribe.call_mut(());
+        self.source.subscribe(observer)
+    }
+}
+
+struct TapObserver<T, E, O, O2>
+where O: Observer<T, E>,
+      O2: Observer<T, E> {
+    observer: O,
+    side_observer: O2,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, O2> Observer<T, E> for TapObserver<T, E, O, O2>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      O2: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        self.side_observer.on_next(item.clone());
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.side_observer.on_completed();
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.side_observer.on_error(error.clone());
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `tap()` on an observable.
+pub struct TapObservable<'a, Source: 'a + ?Sized, O2> {
+    source: &'a mut Source,
+    side_observer: O2,
+}
+
+impl<'a, Source: 'a + ?Sized, O2> TapObservable<'a, Source, O2> {
+    pub fn new(source: &'a mut Source, side_observer: O2) -> TapObservable<'a, Source, O2> {
+        TapObservable {
+            source: source,
+            side_observer: side_observer,
+        }
+    }
+}
+
+impl<'a, Source, O2> Observable for TapObservable<'a, Source, O2>
+where Source: Observable,
+      O2: Observer<<Source as Observable>::Item, <Source as Observable>::Error> + Clone {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let tap_observer = TapObserver {
+            observer: observer,
+            side_observer: self.side_observer.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(tap_observer)
+    }
+}
+
+/// The action of a `finally()` observable, shared by observer and subscription.
+struct FinallyAction<F: Fn()> {
+    f: Rc<F>,
+    done: Cell<bool>,
+}
+
+impl<F: Fn()> FinallyAction<F> {
+    /// Calls the function, unless it has been called already.
+    fn run(&self) {
+        if !self.done.get() {
+            self.done.set(true);
+            self.f.call(());
+        }
+    }
+}
+
+pub struct FinallySubscription<Source: Observable, F: Fn()> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: Source::Subscription,
+    action: Rc<FinallyAction<F>>,
+}
+
+impl<Source: Observable, F: Fn()> Subscription for FinallySubscription<Source, F> { }
+
+impl<Source: Observable, F: Fn()> Drop for FinallySubscription<Source, F> {
+    fn drop(&mut self) {
+        // If the observable did not terminate yet, the observer will not be
+        // called any more, so the action runs now.
+        self.action.run();
+    }
+}
+
+struct FinallyObserver<T, E, O, F>
+where O: Observer<T, E>,
+      F: Fn() {
+    observer: O,
+    action: Rc<FinallyAction<F>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, F> Observer<T, E> for FinallyObserver<T, E, O, F>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      F: Fn() {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+        self.action.run();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+        self.action.run();
+    }
+}
+
+/// The result of calling `finally()` on an observable.
+pub struct FinallyObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: Rc<F>,
+}
+
+impl<'a, Source: 'a + ?Sized, F> FinallyObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> FinallyObservable<'a, Source, F> {
+        FinallyObservable {
+            source: source,
+            f: Rc::new(f),
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for FinallyObservable<'a, Source, F>
+where Source: Observable,
+      F: Fn() {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = FinallySubscription<Source, F>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        // The subscription cannot borrow the function from the observable,
+        // because it may outlive this borrow of the observable.
+        let action = Rc::new(FinallyAction {
+            f: self.f.clone(),
+            done: Cell::new(false),
+        });
+        let finally_observer = FinallyObserver {
+            observer: observer,
+            action: action.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let subs_source = self.source.subscribe(finally_observer);
+        FinallySubscription {
+            subs_source: subs_source,
+            action: action,
+        }
+    }
+}
+
+/// The result of calling `on_unsubscribe()` on an observable.
+pub struct OnUnsubscribeObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> OnUnsubscribeObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> OnUnsubscribeObservable<'a, Source, F> {
+        OnUnsubscribeObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for OnUnsubscribeObservable<'a, Source, F>
+where Source: Observable,
+      F: FnOnce() + Clone {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = OnUnsubscribe<<Source as Observable>::Subscription, F>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let subs_source = self.source.subscribe(observer);
+        OnUnsubscribe::new(subs_source, self.f.clone())
+    }
+}
+
+/// The subscription to a `flat_map()`, `flat_map_limit()`, or `concat_map()` observable.
+pub struct FlatMapSubscription<Source: Observable, ObInner: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: Source::Subscription,
+
+    #[allow(dead_code)] // Same here.
+    subs_inner: lifeline::Lifeline<Vec<ObInner::Subscription>>,
+}
+
+impl<Source: Observable, ObInner: Observable> Subscription for FlatMapSubscription<Source, ObInner> { }
+
+struct FlatMapObserver<T, ObInner, O, F>
+where ObInner: Observable,
+      O: Observer<ObInner::Item, ObInner::Error>,
+      F: Fn(T) -> ObInner {
+    state: Rc<RefCell<MergeState<O>>>,
+    f: F,
+    subscriptions: lifeline::Owner<Vec<ObInner::Subscription>>,
+    _phantom_t: PhantomData<*mut T>,
+}
+
+impl<T, ObInner, O, F> Observer<T, ObInner::Error> for FlatMapObserver<T, ObInner, O, F>
+where T: Clone,
+      ObInner: Observable,
+      O: Observer<ObInner::Item, ObInner::Error>,
+      F: Fn(T) -> ObInner {
+    fn on_next(&mut self, item: T) {
+        if self.state.borrow().observer.is_none() {
+            // An inner observable failed already, do not subscribe any more.
+            return;
+        }
+
+        self.state.borrow_mut().active += 1;
+        let inner_observer = MergeObserver {
+            state: self.state.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let mut inner = self.f.call((item,));
+        let subs_inner = inner.subscribe(inner_observer);
+        self.subscriptions.with_mut_value(move |subs| subs.push(subs_inner));
+    }
+
+    fn on_completed(self) {
+        let source_observer: MergeObserver<ObInner::Item, ObInner::Error, O> = MergeObserver {
+            state: self.state,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        source_observer.on_completed();
+    }
+
+    fn on_error(self, error: ObInner::Error) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `flat_map()` on an observable.
+pub struct FlatMapObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> FlatMapObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> FlatMapObservable<'a, Source, F> {
+        FlatMapObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, ObInner, F> Observable for FlatMapObservable<'a, Source, F>
+where Source: Observable,
+      ObInner: Observable<Error = <Source as Observable>::Error>,
+      F: Fn(<Source as Observable>::Item) -> ObInner {
+    type Item = <ObInner as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = FlatMapSubscription<Source, ObInner>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        // The source counts as an active source, so the observer does not
+        // complete before the source completes.
+        let state = Rc::new(RefCell::new(MergeState {
+            observer: Some(observer),
+            active: 1,
+        }));
+        let (life, owner) = lifeline::new(Vec::new());
+        let flat_map_observer = FlatMapObserver {
+            state: state,
+            f: &self.f,
+            subscriptions: owner,
+            _phantom_t: PhantomData,
+        };
+        let subs_source = self.source.subscribe(flat_map_observer);
+        FlatMapSubscription {
+            subs_source: subs_source,
+            subs_inner: life,
+        }
+    }
+}
+
+/// The result of calling `flatten()` on an observable.
+pub type FlattenObservable<'a, Source> = FlatMapObservable<'a, Source,
+    fn(<Source as Observable>::Item) -> <Source as Observable>::Item>;
+
+pub struct RepeatSequenceSubscription<Source: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: ResubscribeSubscription<Source>,
+}
+
+impl<Source: Observable> Subscription for RepeatSequenceSubscription<Source> { }
+
+struct RepeatSequenceObserver<'a, Source: 'a + ?Sized, O>
+where Source: Observable {
+    state: Rc<RefCell<ResubscribeState<'a, Source, O, usize>>>,
+    subs_source: lifeline::Owner<Option<Source::Subscription>>,
+}
+
+impl<'a, Source: 'a + ?Sized, O> RepeatSequenceObserver<'a, Source, O>
+where Source: Observable,
+      O: Observer<Source::Item, Source::Error> {
+    /// Subscribes to the observable for a new round.
+    fn subscribe(state: &Rc<RefCell<ResubscribeState<'a, Source, O, usize>>>,
+                 subs_source: &lifeline::Owner<Option<Source::Subscription>>) {
+        resubscribe(state, subs_source, || RepeatSequenceObserver {
+            state: state.clone(),
+            subs_source: subs_source.clone(),
+        });
+    }
+}
+
+impl<'a, Source: 'a + ?Sized, O> Observer<Source::Item, Source::Error> for RepeatSequenceObserver<'a, Source, O>
+where Source: Observable,
+      O: Observer<Source::Item, Source::Error> {
+    fn on_next(&mut self, item: Source::Item) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.control == 0 {
+                if let Some(observer) = state.observer.take() {
+                    observer.on_completed();
+                }
+                return;
+            }
+            state.control -= 1;
+        }
+        RepeatSequenceObserver::subscribe(&self.state, &self.subs_source);
+    }
+
+    fn on_error(self, error: Source::Error) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `repeat()` on an observable.
+pub struct RepeatSequenceObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    count: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> RepeatSequenceObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, count: usize) -> RepeatSequenceObservable<'a, Source> {
+        RepeatSequenceObservable {
+            source: source,
+            count: count,
+        }
+    }
+}
+
+impl<'a, Source> Observable for RepeatSequenceObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = RepeatSequenceSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (life, owner) = lifeline::new(None);
+        if self.count == 0 {
+            observer.on_completed();
+            return RepeatSequenceSubscription {
+                subs_source: life,
+            };
+        }
+
+        let state = Rc::new(RefCell::new(ResubscribeState {
+            observer: Some(observer),
+            source: Some(&mut *self.source),
+            control: self.count - 1,
+            resubscribe: false,
+        }));
+        RepeatSequenceObserver::subscribe(&state, &owner);
+        RepeatSequenceSubscription {
+            subs_source: life,
+        }
+    }
+}
+
+pub struct RepeatWhenSubscription<Source: Observable, ObNotifier: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: ResubscribeSubscription<Source>,
+
+    #[allow(dead_code)] // Same here.
+    subs_notifier: ObNotifier::Subscription,
+}
+
+impl<Source, ObNotifier> Subscription for RepeatWhenSubscription<Source, ObNotifier>
+where Source: Observable,
+      ObNotifier: Observable { }
+
+/// Decides when a repeat-when observable subscribes again or completes.
+struct RepeatWhenControl {
+    /// Whether the current round of the source completed.
+    source_completed: bool,
+
+    /// Whether the notifier completed.
+    notifier_completed: bool,
+}
+
+/// State shared by the observers of a repeat-when observable.
+type RepeatWhenState<'a, Source, O> = Rc<RefCell<ResubscribeState<'a, Source, O, RepeatWhenControl>>>;
+
+struct RepeatWhenObserver<'a, Source: 'a + ?Sized, O>
+where Source: Observable {
+    state: RepeatWhenState<'a, Source, O>,
+}
+
+impl<'a, Source: 'a + ?Sized, O> RepeatWhenObserver<'a, Source, O>
+where Source: Observable,
+      O: Observer<Source::Item, Source::Error> {
+    /// Subscribes to the observable for a new round.
+    fn subscribe(state: &RepeatWhenState<'a, Source, O>,
+                 subs_source: &lifeline::Owner<Option<Source::Subscription>>) {
+        resubscribe(state, subs_source, || RepeatWhenObserver {
+            state: state.clone(),
+        });
+    }
+}
+
+impl<'a, Source: 'a + ?Sized, O> Observer<Source::Item, Source::Error> for RepeatWhenObserver<'a, Source, O>
+where Source: Observable,
+      O: Observer<Source::Item, Source::Error> {
+    fn on_next(&mut self, item: Source::Item) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.control.source_completed = true;
+        if state.control.notifier_completed {
+            if let Some(observer) = state.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn on_error(self, error: Source::Error) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct RepeatWhenNotifierObserver<'a, Source: 'a + ?Sized, O>
+where Source: Observable {
+    state: RepeatWhenState<'a, Source, O>,
+    subs_source: lifeline::Owner<Option<Source::Subscription>>,
+}
+
+impl<'a, Source: 'a + ?Sized, O, N> Observer<N, Source::Error> for RepeatWhenNotifierObserver<'a, Source, O>
+where Source: Observable,
+      O: Observer<Source::Item, Source::Error> {
+    fn on_next(&mut self, _item: N) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.observer.is_none() || !state.control.source_completed {
+                // Only a completed source can be subscribed to again.
+                return;
+            }
+            state.control.source_completed = false;
+        }
+        RepeatWhenObserver::subscribe(&self.state, &self.subs_source);
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.control.notifier_completed = true;
+        if state.control.source_completed {
+            if let Some(observer) = state.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn on_error(self, error: Source::Error) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `repeat_when()` on an observable.
+pub struct RepeatWhenObservable<'a, Source: 'a + ?Sized, ObNotifier: 'a + ?Sized> {
+    source: &'a mut Source,
+    notifier: &'a mut ObNotifier,
+}
+
+impl<'a, Source: 'a + ?Sized, ObNotifier: 'a + ?Sized> RepeatWhenObservable<'a, Source, ObNotifier> {
+    pub fn new(source: &'a mut Source, notifier: &'a mut ObNotifier) -> RepeatWhenObservable<'a, Source, ObNotifier> {
+        RepeatWhenObservable {
+            source: source,
+            notifier: notifier,
+        }
+    }
+}
+
+impl<'a, Source, ObNotifier> Observable for RepeatWhenObservable<'a, Source, ObNotifier>
+where Source: Observable,
+      ObNotifier: Observable<Error = <Source as Observable>::Error> {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = RepeatWhenSubscription<Source, ObNotifier>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(ResubscribeState {
+            observer: Some(observer),
+            source: Some(&mut *self.source),
+            control: RepeatWhenControl {
+                source_completed: false,
+                notifier_completed: false,
+            },
+            resubscribe: false,
+        }));
+        let (life, owner) = lifeline::new(None);
+        RepeatWhenObserver::subscribe(&state, &owner);
+
+        let notifier_observer = RepeatWhenNotifierObserver {
+            state: state,
+            subs_source: owner,
+        };
+        let subs_notifier = self.notifier.subscribe(notifier_observer);
+        RepeatWhenSubscription {
+            subs_source: life,
+            subs_notifier: subs_notifier,
+        }
+    }
+}
+
+/// State shared by the two observers of a zipped observable.
+struct ZipState<A, B, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// Values of the first source that have not been paired yet.
+    first: VecDeque<A>,
+
+    /// Values of the second source that have not been paired yet.
+    second: VecDeque<B>,
+
+    /// Whether the first source completed.
+    first_completed: bool,
+
+    /// Whether the second source completed.
+    second_completed: bool,
+}
+
+impl<A, B, O> ZipState<A, B, O> {
+    /// Pushes pairs while both queues are non-empty, and completes if a source
+    /// completed and its queue is empty, because then no more pairs can be
+    /// formed.
+    fn push_pairs<E>(&mut self) where O: Observer<(A, B), E> {
+        if let Some(ref mut observer) = self.observer {
+            while !self.first.is_empty() && !self.second.is_empty() {
+                let a = self.first.pop_front().unwrap();
+                let b = self.second.pop_front().unwrap();
+                observer.on_next((a, b));
+            }
+        }
+
+        let first_done = self.first_completed && self.first.is_empty();
+        let second_done = self.second_completed && self.second.is_empty();
+        if first_done || second_done {
+            if let Some(observer) = self.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn fail<E>(&mut self, error: E) where O: Observer<(A, B), E> {
+        if let Some(observer) = self.observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct ZipFirstObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    state: Rc<RefCell<ZipState<A, B, O>>>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<A, B, E, O> Observer<A, E> for ZipFirstObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    fn on_next(&mut self, item: A) {
+        let mut state = self.state.borrow_mut();
+        state.first.push_back(item);
+        state.push_pairs();
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.first_completed = true;
+        state.push_pairs();
+    }
+
+    fn on_error(self, error: E) {
+        self.state.borrow_mut().fail(error);
+    }
+}
+
+struct ZipSecondObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    state: Rc<RefCell<ZipState<A, B, O>>>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<A, B, E, O> Observer<B, E> for ZipSecondObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    fn on_next(&mut self, item: B) {
+        let mut state = self.state.borrow_mut();
+        state.second.push_back(item);
+        state.push_pairs();
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.second_completed = true;
+        state.push_pairs();
+    }
+
+    fn on_error(self, error: E) {
+        self.state.borrow_mut().fail(error);
+    }
+}
+
+/// The result of calling `zip()` on an observable.
+pub struct ZipObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> {
+    source: &'a mut Source,
+    other: &'a mut ObOther,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> ZipObservable<'a, Source, ObOther> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther) -> ZipObservable<'a, Source, ObOther> {
+        ZipObservable {
+            source: source,
+            other: other,
+        }
+    }
+}
+
+impl<'a, E: Clone, Source, ObOther> Observable for ZipObservable<'a, Source, ObOther>
+where Source: Observable<Error = E>,
+      ObOther: Observable<Error = E> {
+    type Item = (<Source as Observable>::Item, <ObOther as Observable>::Item);
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(ZipState {
+            observer: Some(observer),
+            first: VecDeque::new(),
+            second: VecDeque::new(),
+            first_completed: false,
+            second_completed: false,
+        }));
+        let first_observer = ZipFirstObserver {
+            state: state.clone(),
+            _phantom_e: PhantomData,
+        };
+        let second_observer = ZipSecondObserver {
+            state: state,
+            _phantom_e: PhantomData,
+        };
+        let subs_source = self.source.subscribe(first_observer);
+        let subs_other = self.other.subscribe(second_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+/// The result of calling `zip_with()` on an observable.
+pub struct ZipWithObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized, F> {
+    zip: ZipObservable<'a, Source, ObOther>,
+    combine: F,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized, F> ZipWithObservable<'a, Source, ObOther, F> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther, combine: F) -> ZipWithObservable<'a, Source, ObOther, F> {
+        ZipWithObservable {
+            zip: ZipObservable::new(source, other),
+            combine: combine,
+        }
+    }
+}
+
+impl<'a, E: Clone, U: Clone, Source, ObOther, F> Observable for ZipWithObservable<'a, Source, ObOther, F>
+where Source: Observable<Error = E>,
+      ObOther: Observable<Error = E>,
+      F: Fn(<Source as Observable>::Item, <ObOther as Observable>::Item) -> U {
+    type Item = U;
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let combine = &self.combine;
+        self.zip.subscribe(map_observer(observer, move |(a, b)| combine.call((a, b))))
+    }
+}
+
+/// State shared by the observers of the sources of `zip_all()`.
+struct ZipAllState<T, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// Per source, the values that have not been pushed yet.
+    queues: Vec<VecDeque<T>>,
+
+    /// Per source, whether it completed.
+    completed: Vec<bool>,
+}
+
+impl<T, O> ZipAllState<T, O> {
+    /// Pushes values while all queues are non-empty, and completes if a source
+    /// completed and its queue is empty.
+    fn push_values<E>(&mut self) where O: Observer<Vec<T>, E> {
+        if let Some(ref mut observer) = self.observer {
+            while self.queues.iter().all(|queue| !queue.is_empty()) {
+                let values = self.queues.iter_mut()
+                                        .map(|queue| queue.pop_front().unwrap())
+                                        .collect();
+                observer.on_next(values);
+            }
+        }
+
+        let done = self.queues.iter().zip(self.completed.iter()).any(|(queue, &completed)| {
+            completed && queue.is_empty()
+        });
+        if done {
+            if let Some(observer) = self.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+}
+
+struct ZipAllObserver<T, E, O, S>
+where O: Observer<Vec<T>, E> {
+    state: Rc<RefCell<ZipAllState<T, O>>>,
+    subscriptions: lifeline::Owner<Vec<S>>,
+    /// The index of the source that this observer is subscribed to.
+    index: usize,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> Observer<T, E> for ZipAllObserver<T, E, O, S>
+where O: Observer<Vec<T>, E> {
+    fn on_next(&mut self, item: T) {
+        let mut state = self.state.borrow_mut();
+        state.queues[self.index].push_back(item);
+        state.push_values();
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.completed[self.index] = true;
+        state.push_values();
+    }
+
+    fn on_error(self, error: E) {
+        let observer = self.state.borrow_mut().observer.take();
+        if let Some(observer) = observer {
+            observer.on_error(error);
+        }
+        // Drop the subscriptions to the other sources. The state must not be
+        // borrowed here, because dropping them drops their observers too.
+        drop(self.subscriptions.take());
+    }
+}
+
+/// The subscription to the observables zipped by `zip_all()`.
+pub struct ZipAllSubscription<Ob: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscriptions alive.
+    subscriptions: lifeline::Lifeline<Vec<Ob::Subscription>>,
+}
+
+impl<Ob: Observable> Subscription for ZipAllSubscription<Ob> { }
+
+/// The result of calling `zip_all()` on a slice of observables.
+pub struct ZipAllObservable<'a, Ob: 'a> {
+    sources: &'a mut [Ob],
+}
+
+impl<'a, Ob: 'a> ZipAllObservable<'a, Ob> {
+    pub fn new(sources: &'a mut [Ob]) -> ZipAllObservable<'a, Ob> {
+        ZipAllObservable {
+            sources: sources,
+        }
+    }
+}
+
+impl<'a, Ob> Observable for ZipAllObservable<'a, Ob>
+where Ob: Observable {
+    type Item = Vec<<Ob as Observable>::Item>;
+    type Error = <Ob as Observable>::Error;
+    type Subscription = ZipAllSubscription<Ob>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (mut life, _) = lifeline::new(Vec::with_capacity(self.sources.len()));
+        if self.sources.is_empty() {
+            observer.on_completed();
+        } else {
+            let state = Rc::new(RefCell::new(ZipAllState {
+                observer: Some(observer),
+                queues: self.sources.iter().map(|_| VecDeque::new()).collect(),
+                completed: vec![false; self.sources.len()],
+            }));
+            for (index, source) in self.sources.iter_mut().enumerate() {
+                // Stop subscribing if the zipped observable terminated already,
+                // and drop the subscriptions to the sources before it.
+                if state.borrow().observer.is_none() {
+                    life.cancel();
+                    break;
+                }
+                let zip_observer = ZipAllObserver {
+                    state: state.clone(),
+                    subscriptions: life.owner(),
+                    index: index,
+                    _phantom_e: PhantomData,
+                };
+                let subscription = source.subscribe(zip_observer);
+
+                // If a source failed during subscription, the subscriptions
+                // have been dropped, and this one is dropped immediately.
+                life.with_mut_value(move |subs| subs.push(subscription));
+            }
+        }
+        ZipAllSubscription {
+            subscriptions: life,
+        }
+    }
+}
+
+/// Zips a sequence of observables into an observable of vectors.
+///
+/// The n-th vector contains the n-th value of every observable, in the order
+/// of the observables. The zipped observable completes as soon as one of the
+/// observables completed and all of its values have been pushed, or
+/// immediately if there are no observables. If any of the observables fails,
+/// the error is passed through and the subscriptions to the other observables
+/// are dropped.
+///
+/// See also `Observable::zip()`.
+pub fn zip_all<'s, Ob>(sources: &'s mut [Ob]) -> ZipAllObservable<'s, Ob>
+    where Ob: Observable {
+    ZipAllObservable::new(sources)
+}
+
+/// State shared by the two observers of a combined observable.
+struct CombineLatestState<A, B, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// The most recent value of the first source.
+    first: Option<A>,
+
+    /// The most recent value of the second source.
+    second: Option<B>,
+
+    /// The number of sources that have not completed yet.
+    active: usize,
+}
+
+impl<A: Clone, B: Clone, O> CombineLatestState<A, B, O> {
+    /// Pushes the most recent values if both sources produced a value.
+    fn push_latest<E>(&mut self) where O: Observer<(A, B), E> {
+        if let (Some(a), Some(b)) = (self.first.as_ref(), self.second.as_ref()) {
+            if let Some(ref mut observer) = self.observer {
+                observer.on_next((a.clone(), b.clone()));
+            }
+        }
+    }
+
+    fn complete_source<E>(&mut self) where O: Observer<(A, B), E> {
+        self.active -= 1;
+        if self.active == 0 {
+            if let Some(observer) = self.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn fail<E>(&mut self, error: E) where O: Observer<(A, B), E> {
+        if let Some(observer) = self.observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct CombineLatestFirstObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    state: Rc<RefCell<CombineLatestState<A, B, O>>>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<A, B, E, O> Observer<A, E> for CombineLatestFirstObserver<A, B, E, O>
+where A: Clone,
+      B: Clone,
+      O: Observer<(A, B), E> {
+    fn on_next(&mut self, item: A) {
+        let mut state = self.state.borrow_mut();
+        state.first = Some(item);
+        state.push_latest();
+    }
+
+    fn on_completed(self) {
+        self.state.borrow_mut().complete_source();
+    }
+
+    fn on_error(self, error: E) {
+        self.state.borrow_mut().fail(error);
+    }
+}
+
+struct CombineLatestSecondObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    state: Rc<RefCell<CombineLatestState<A, B, O>>>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<A, B, E, O> Observer<B, E> for CombineLatestSecondObserver<A, B, E, O>
+where A: Clone,
+      B: Clone,
+      O: Observer<(A, B), E> {
+    fn on_next(&mut self, item: B) {
+        let mut state = self.state.borrow_mut();
+        state.second = Some(item);
+        state.push_latest();
+    }
+
+    fn on_completed(self) {
+        self.state.borrow_mut().complete_source();
+    }
+
+    fn on_error(self, error: E) {
+        self.state.borrow_mut().fail(error);
+    }
+}
+
+/// The result of calling `combine_latest()` on an observable.
+pub struct CombineLatestObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> {
+    source: &'a mut Source,
+    other: &'a mut ObOther,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> CombineLatestObservable<'a, Source, ObOther> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther) -> CombineLatestObservable<'a, Source, ObOther> {
+        CombineLatestObservable {
+            source: source,
+            other: other,
+        }
+    }
+}
+
+impl<'a, E: Clone, Source, ObOther> Observable for CombineLatestObservable<'a, Source, ObOther>
+where Source: Observable<Error = E>,
+      ObOther: Observable<Error = E> {
+    type Item = (<Source as Observable>::Item, <ObOther as Observable>::Item);
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(CombineLatestState {
+            observer: Some(observer),
+            first: None,
+            second: None,
+            active: 2,
+        }));
+        let first_observer = CombineLatestFirstObserver {
+            state: state.clone(),
+            _phantom_e: PhantomData,
+        };
+        let second_observer = CombineLatestSecondObserver {
+            state: state,
+            _phantom_e: PhantomData,
+        };
+        let subs_source = self.source.subscribe(first_observer);
+        let subs_other = self.other.subscribe(second_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+/// The result of calling `combine_latest_with()` on an observable.
+pub struct CombineLatestWithObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized, F> {
+    combine_latest: CombineLatestObservable<'a, Source, ObOther>,
+    combine: F,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized, F> CombineLatestWithObservable<'a, Source, ObOther, F> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther, combine: F) -> CombineLatestWithObservable<'a, Source, ObOther, F> {
+        CombineLatestWithObservable {
+            combine_latest: CombineLatestObservable::new(source, other),
+            combine: combine,
+        }
+    }
+}
+
+impl<'a, E: Clone, U: Clone, Source, ObOther, F> Observable for CombineLatestWithObservable<'a, Source, ObOther, F>
+where Source: Observable<Error = E>,
+      ObOther: Observable<Error = E>,
+      F: Fn(<Source as Observable>::Item, <ObOther as Observable>::Item) -> U {
+    type Item = U;
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let combine = &self.combine;
+        self.combine_latest.subscribe(map_observer(observer, move |(a, b)| combine.call((a, b))))
+    }
+}
+
+/// State shared by the two observers of a `with_latest_from()` observable.
+struct WithLatestFromState<B, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// The most recent value of the other source.
+    latest: Option<B>,
+}
+
+struct WithLatestFromObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    state: Rc<RefCell<WithLatestFromState<B, O>>>,
+    _phantom_a: PhantomData<*mut A>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<A, B, E, O> Observer<A, E> for WithLatestFromObserver<A, B, E, O>
+where B: Clone,
+      O: Observer<(A, B), E> {
+    fn on_next(&mut self, item: A) {
+        let mut state = self.state.borrow_mut();
+        let WithLatestFromState { ref mut observer, ref latest } = *state;
+        if let (Some(observer), Some(b)) = (observer.as_mut(), latest.as_ref()) {
+            observer.on_next((item, b.clone()));
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct WithLatestFromOtherObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    state: Rc<RefCell<WithLatestFromState<B, O>>>,
+    _phantom_a: PhantomData<*mut A>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<A, B, E, O> Observer<B, E> for WithLatestFromOtherObserver<A, B, E, O>
+where O: Observer<(A, B), E> {
+    fn on_next(&mut self, item: B) {
+        self.state.borrow_mut().latest = Some(item);
+    }
+
+    fn on_completed(self) {
+        // The most recent value remains available, nothing changes.
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `with_latest_from()` on an observable.
+pub struct WithLatestFromObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> {
+    source: &'a mut Source,
+    other: &'a mut ObOther,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> WithLatestFromObservable<'a, Source, ObOther> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther) -> WithLatestFromObservable<'a, Source, ObOther> {
+        WithLatestFromObservable {
+            source: source,
+            other: other,
+        }
+    }
+}
+
+impl<'a, E: Clone, Source, ObOther> Observable for WithLatestFromObservable<'a, Source, ObOther>
+where Source: Observable<Error = E>,
+      ObOther: Observable<Error = E> {
+    type Item = (<Source as Observable>::Item, <ObOther as Observable>::Item);
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(WithLatestFromState {
+            observer: Some(observer),
+            latest: None,
+        }));
+        let source_observer = WithLatestFromObserver {
+            state: state.clone(),
+            _phantom_a: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let other_observer = WithLatestFromOtherObserver {
+            state: state,
+            _phantom_a: PhantomData,
+            _phantom_e: PhantomData,
+        };
+
+        // Subscribe to the other observable first, so a value that it pushes
+        // upon subscription is available when the source pushes its values.
+        let subs_other = self.other.subscribe(other_observer);
+        let subs_source = self.source.subscribe(source_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+struct BufferCountObserver<T, E, O>
+where O: Observer<Vec<T>, E> {
+    observer: O,
+    size: usize,
+    buffer: Vec<T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for BufferCountObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<Vec<T>, E> {
+    fn on_next(&mut self, item: T) {
+        use std::mem;
+        self.buffer.push(item);
+        if self.buffer.len() == self.size {
+            let buffer = mem::replace(&mut self.buffer, Vec::with_capacity(self.size));
+            self.observer.on_next(buffer);
+        }
+    }
+
+    fn on_completed(mut self) {
+        if !self.buffer.is_empty() {
+            self.observer.on_next(self.buffer);
+        }
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `buffer_count()` on an observable.
+pub struct BufferCountObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    size: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> BufferCountObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, size: usize) -> BufferCountObservable<'a, Source> {
+        assert!(size > 0, "buffer size must be at least 1");
+        BufferCountObservable {
+            source: source,
+            size: size,
+        }
+    }
+}
+
+impl<'a, Source> Observable for BufferCountObservable<'a, Source>
+where Source: Observable {
+    type Item = Vec<<Source as Observable>::Item>;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let buffer_observer = BufferCountObserver {
+            observer: observer,
+            size: self.size,
+            buffer: Vec::with_capacity(self.size),
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(buffer_observer)
+    }
+}
+
+/// A window of values, the item of a `window_count()` observable.
+///
+/// A window is an observable that pushes the values of its part of the source
+/// observable. Values are only pushed to observers that subscribed before the
+/// value was produced, so a window should be subscribed to as soon as it is
+/// received.
+#[derive(Clone)]
+pub struct Window<T, E> {
+    subject: SubjectHandle<T, E>,
+}
+
+impl<T: Clone, E: Clone> Observable for Window<T, E> {
+    type Item = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subject.subscribe(observer)
+    }
+}
+
+struct WindowCountObserver<T, E, O>
+where O: Observer<Window<T, E>, E> {
+    observer: O,
+    size: usize,
+    window: Option<Subject<T, E>>,
+    count: usize,
+}
+
+impl<T, E, O> Observer<T, E> for WindowCountObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<Window<T, E>, E> {
+    fn on_next(&mut self, item: T) {
+        if self.window.is_none() {
+            // Open a new window, and push it first so the observer can
+            // subscribe to it before it receives the value.
+            let subject = Subject::new();
+            let window = Window { subject: subject.handle() };
+            self.window = Some(subject);
+            self.count = 0;
+            self.observer.on_next(window);
+        }
+
+        if let Some(ref mut window) = self.window {
+            window.on_next(item);
+        }
+        self.count += 1;
+
+        if self.count == self.size {
+            if let Some(subject) = self.window.take() {
+                subject.on_completed();
+            }
+        }
+    }
+
+    fn on_completed(mut self) {
+        if let Some(subject) = self.window.take() {
+            subject.on_completed();
+        }
+        self.observer.on_completed();
+    }
+
+    fn on_error(mut self, error: E) {
+        if let Some(subject) = self.window.take() {
+            subject.on_error(error.clone());
+        }
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `window_count()` on an observable.
+pub struct WindowCountObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    size: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> WindowCountObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, size: usize) -> WindowCountObservable<'a, Source> {
+        assert!(size > 0, "window size must be at least 1");
+        WindowCountObservable {
+            source: source,
+            size: size,
+        }
+    }
+}
+
+impl<'a, Source> Observable for WindowCountObservable<'a, Source>
+where Source: Observable {
+    type Item = Window<<Source as Observable>::Item, <Source as Observable>::Error>;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let window_observer = WindowCountObserver {
+            observer: observer,
+            size: self.size,
+            window: None,
+            count: 0,
+        };
+        self.source.subscribe(window_observer)
+    }
+}
+
+struct ToVecObserver<T, E, O>
+where O: Observer<Vec<T>, E> {
+    observer: O,
+    values: Vec<T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for ToVecObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<Vec<T>, E> {
+    fn on_next(&mut self, item: T) {
+        self.values.push(item);
+    }
+
+    fn on_completed(mut self) {
+        self.observer.on_next(self.values);
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `to_vec()` on an observable.
+pub struct ToVecObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> ToVecObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> ToVecObservable<'a, Source> {
+        ToVecObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for ToVecObservable<'a, Source>
+where Source: Observable {
+    type Item = Vec<<Source as Observable>::Item>;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let to_vec_observer = ToVecObserver {
+            observer: observer,
+            values: Vec::new(),
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(to_vec_observer)
+    }
+}
+
+struct AllObserver<T, E, O, P, S>
+where O: Observer<bool, E>,
+      P: Fn(&T) -> bool {
+    observer: Option<O>,
+    predicate: P,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, P, S> Observer<T, E> for AllObserver<T, E, O, P, S>
+where T: Clone,
+      E: Clone,
+      O: Observer<bool, E>,
+      P: Fn(&T) -> bool {
+    fn on_next(&mut self, item: T) {
+        if self.observer.is_some() && !self.predicate.call((&item,)) {
+            if let Some(mut observer) = self.observer.take() {
+                observer.on_next(false);
+                observer.on_completed();
+                self.unsubscriber.unsubscribe();
+            }
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(mut observer) = self.observer {
+            observer.on_next(true);
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `all()` on an observable.
+pub struct AllObservable<'a, Source: 'a + ?Sized, P> {
+    source: &'a mut Source,
+    predicate: P,
+}
+
+impl<'a, Source: 'a + ?Sized, P> AllObservable<'a, Source, P> {
+    pub fn new(source: &'a mut Source, predicate: P) -> AllObservable<'a, Source, P> {
+        AllObservable {
+            source: source,
+            predicate: predicate,
+        }
+    }
+}
+
+impl<'a, Source, P> Observable for AllObservable<'a, Source, P>
+where Source: Observable,
+      P: Fn(&<Source as Observable>::Item) -> bool {
+    type Item = bool;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ShortCircuitSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let predicate = &self.predicate;
+        ShortCircuitSubscription::subscribe(self.source, |unsubscriber| AllObserver {
+            observer: Some(observer),
+            predicate: predicate,
+            unsubscriber: unsubscriber,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        })
+    }
+}
+
+struct AnyObserver<T, E, O, P, S>
+where O: Observer<bool, E>,
+      P: Fn(&T) -> bool {
+    observer: Option<O>,
+    predicate: P,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, P, S> Observer<T, E> for AnyObserver<T, E, O, P, S>
+where T: Clone,
+      E: Clone,
+      O: Observer<bool, E>,
+      P: Fn(&T) -> bool {
+    fn on_next(&mut self, item: T) {
+        if self.observer.is_some() && self.predicate.call((&item,)) {
+            if let Some(mut observer) = self.observer.take() {
+                observer.on_next(true);
+                observer.on_completed();
+                self.unsubscriber.unsubscribe();
+            }
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(mut observer) = self.observer {
+            observer.on_next(false);
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `any()` on an observable.
+pub struct AnyObservable<'a, Source: 'a + ?Sized, P> {
+    source: &'a mut Source,
+    predicate: P,
+}
+
+impl<'a, Source: 'a + ?Sized, P> AnyObservable<'a, Source, P> {
+    pub fn new(source: &'a mut Source, predicate: P) -> AnyObservable<'a, Source, P> {
+        AnyObservable {
+            source: source,
+            predicate: predicate,
+        }
+    }
+}
+
+impl<'a, Source, P> Observable for AnyObservable<'a, Source, P>
+where Source: Observable,
+      P: Fn(&<Source as Observable>::Item) -> bool {
+    type Item = bool;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ShortCircuitSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let predicate = &self.predicate;
+        ShortCircuitSubscription::subscribe(self.source, |unsubscriber| AnyObserver {
+            observer: Some(observer),
+            predicate: predicate,
+            unsubscriber: unsubscriber,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        })
+    }
+}
+
+struct DistinctByObserver<T, E, O, K, F>
+where O: Observer<T, E>,
+      K: Eq + Hash,
+      F: Fn(&T) -> K {
+    observer: O,
+    key: F,
+    seen: HashSet<K>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, K, F> Observer<T, E> for DistinctByObserver<T, E, O, K, F>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      K: Eq + Hash,
+      F: Fn(&T) -> K {
+    fn on_next(&mut self, item: T) {
+        // Insert returns false if the key was present already.
+        if self.seen.insert(self.key.call((&item,))) {
+            self.observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `distinct_by()` on an observable.
+pub struct DistinctByObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    key: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> DistinctByObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, key: F) -> DistinctByObservable<'a, Source, F> {
+        DistinctByObservable {
+            source: source,
+            key: key,
+        }
+    }
+}
+
+impl<'a, Source, K, F> Observable for DistinctByObservable<'a, Source, F>
+where Source: Observable,
+      K: Eq + Hash,
+      F: Fn(&<Source as Observable>::Item) -> K {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        // Every subscription starts with a clean slate.
+        let distinct_observer = DistinctByObserver {
+            observer: observer,
+            key: &self.key,
+            seen: HashSet::new(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(distinct_observer)
+    }
+}
+
+/// The result of calling `distinct()` on an observable.
+pub type DistinctObservable<'a, Source> = DistinctByObservable<'a, Source,
+    fn(&<Source as Observable>::Item) -> <Source as Observable>::Item>;
+
+/// State shared by the subscriptions to a shared observable.
+struct ShareState<T, E, S> {
+    /// The subject that the source pushes into, if connected.
+    handle: Option<SubjectHandle<T, E>>,
+    subs_source: Option<S>,
+    count: usize,
+}
+
+struct ShareObserver<T, E, S> {
+    subject: Subject<T, E>,
+    // This is a weak reference, because the state owns the subscription to
+    // the source, which might own this observer.
+    state: Weak<RefCell<ShareState<T, E, S>>>,
+}
+
+impl<T, E, S> ShareObserver<T, E, S> {
+    /// Marks the subject as terminated, so the next subscription reconnects.
+    fn disconnect(&self) {
+        if let Some(state) = self.state.upgrade() {
+            state.borrow_mut().handle = None;
+        }
+    }
+}
+
+impl<T, E, S> Observer<T, E> for ShareObserver<T, E, S>
+where T: Clone,
+      E: Clone {
+    fn on_next(&mut self, item: T) {
+        self.subject.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.disconnect();
+        self.subject.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.disconnect();
+        self.subject.on_error(error);
+    }
+}
+
+/// The subscription to a shared observable.
+///
+/// When the last subscription is dropped, the subscription to the source is
+/// dropped as well.
+pub struct SharedSubscription<T, E, S> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_subject: SubjectSubscription<T, E>,
+    state: Rc<RefCell<ShareState<T, E, S>>>,
+}
+
+impl<T, E, S> Subscription for SharedSubscription<T, E, S> { }
+
+impl<T, E, S> Drop for SharedSubscription<T, E, S> {
+    fn drop(&mut self) {
+        let subs_source = {
+            let mut state = self.state.borrow_mut();
+            state.count -= 1;
+            if state.count == 0 {
+                state.handle = None;
+                state.subs_source.take()
+            } else {
+                None
+            }
+        };
+        // The state must not be borrowed while the source subscription is
+        // dropped, in case that ends up touching the state.
+        drop(subs_source);
+    }
+}
+
+/// The share state for the items, errors and subscription of a source.
+type SharedState<Source> = Rc<RefCell<ShareState<<Source as Observable>::Item,
+                                                 <Source as Observable>::Error,
+                                                 <Source as Observable>::Subscription>>>;
+
+/// The result of calling `share()` on an observable.
+pub struct SharedObservable<'a, Source: 'a + Observable + ?Sized> {
+    source: &'a mut Source,
+    state: SharedState<Source>,
+}
+
+impl<'a, Source: 'a + Observable + ?Sized> SharedObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> SharedObservable<'a, Source> {
+        let state = ShareState {
+            handle: None,
+            subs_source: None,
+            count: 0,
+        };
+        SharedObservable {
+            source: source,
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+}
+
+impl<'a, Source> Observable for SharedObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = SharedSubscription<Self::Item, Self::Error, <Source as Observable>::Subscription>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (mut handle, connect) = {
+            let mut state = self.state.borrow_mut();
+            state.count += 1;
+            match state.handle {
+                Some(ref handle) => (handle.clone(), None),
+                None => {
+                    let subject = Subject::new();
+                    state.handle = Some(subject.handle());
+                    (subject.handle(), Some(subject))
+                }
+            }
+        };
+
+        // Subscribe to the subject before connecting it to the source, so the
+        // observer receives the values that the source pushes upon subscription.
+        let subs_subject = handle.subscribe(observer);
+
+        if let Some(subject) = connect {
+            let share_observer = ShareObserver {
+                subject: subject,
+                state: Rc::downgrade(&self.state),
+            };
+            let subs_source = self.source.subscribe(share_observer);
+
+            // If the source terminated before, this replaces the subscription
+            // of the previous connection.
+            let old_subs_source = self.state.borrow_mut().subs_source.take();
+            drop(old_subs_source);
+            self.state.borrow_mut().subs_source = Some(subs_source);
+        }
+
+        SharedSubscription {
+            subs_subject: subs_subject,
+            state: self.state.clone(),
+        }
+    }
+}
+
+struct SampleEveryObserver<T, E, O>
+where O: Observer<T, E> {
+    observer: O,
+    n: usize,
+    // The number of values to drop before the next value to push.
+    remaining: usize,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for SampleEveryObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+        } else {
+            self.remaining = self.n - 1;
+            self.observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `sample_every()` on an observable.
+pub struct SampleEveryObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    n: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> SampleEveryObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, n: usize) -> SampleEveryObservable<'a, Source> {
+        assert!(n > 0, "sample interval must be at least 1");
+        SampleEveryObservable {
+            source: source,
+            n: n,
+        }
+    }
+}
+
+impl<'a, Source> Observable for SampleEveryObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let sample_observer = SampleEveryObserver {
+            observer: observer,
+            n: self.n,
+            remaining: 0,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(sample_observer)
+    }
+}
+
+/// The result of calling `sample_first_of()` on an observable.
+pub type SampleFirstOfObservable<'a, Source> = SampleEveryObservable<'a, Source>;
+
+struct EnumerateObserver<T, E, O>
+where O: Observer<(usize, T), E> {
+    observer: O,
+    index: usize,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for EnumerateObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<(usize, T), E> {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next((self.index, item));
+        self.index += 1;
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `enumerate()` on an observable.
+pub struct EnumerateObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> EnumerateObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> EnumerateObservable<'a, Source> {
+        EnumerateObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for EnumerateObservable<'a, Source>
+where Source: Observable {
+    type Item = (usize, <Source as Observable>::Item);
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        // Every observer gets its own counter, so indices start at 0 for
+        // every subscription.
+        let enumerate_observer = EnumerateObserver {
+            observer: observer,
+            index: 0,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(enumerate_observer)
+    }
+}
+
+struct MaterializeObserver<T, E, O>
+where O: Observer<Notification<T, E>, ()> {
+    observer: O,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for MaterializeObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<Notification<T, E>, ()> {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(Notification::Next(item));
+    }
+
+    fn on_completed(mut self) {
+        self.observer.on_next(Notification::Completed);
+        self.observer.on_completed();
+    }
+
+    fn on_error(mut self, error: E) {
+        // The error becomes a value, so the materialized observable completes.
+        self.observer.on_next(Notification::Error(error));
+        self.observer.on_completed();
+    }
+}
+
+/// The result of calling `materialize()` on an observable.
+pub struct MaterializeObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> MaterializeObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> MaterializeObservable<'a, Source> {
+        MaterializeObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for MaterializeObservable<'a, Source>
+where Source: Observable {
+    type Item = Notification<<Source as Observable>::Item, <Source as Observable>::Error>;
+    type Error = ();
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let materialize_observer = MaterializeObserver {
+            observer: observer,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(materialize_observer)
+    }
+}
+
+struct DematerializeObserver<T, E, O>
+where O: Observer<T, E> {
+    // This is `None` after a terminal notification.
+    observer: Option<O>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<Notification<T, E>, ()> for DematerializeObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, notification: Notification<T, E>) {
+        match notification {
+            Notification::Next(item) => {
+                if let Some(ref mut observer) = self.observer {
+                    observer.on_next(item);
+                }
+            }
+            Notification::Completed => {
+                if let Some(observer) = self.observer.take() {
+                    observer.on_completed();
+                }
+            }
+            Notification::Error(error) => {
+                if let Some(observer) = self.observer.take() {
+                    observer.on_error(error);
+                }
+            }
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.observer {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, _error: ()) {
+        // The error carries no information, so there is nothing to pass on.
+        if let Some(observer) = self.observer {
+            observer.on_completed();
+        }
+    }
+}
+
+/// The result of calling `dematerialize()` on an observable.
+pub struct DematerializeObservable<'a, Source: 'a + ?Sized, T, E> {
+    source: &'a mut Source,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<'a, Source: 'a + ?Sized, T, E> DematerializeObservable<'a, Source, T, E> {
+    pub fn new(source: &'a mut Source) -> DematerializeObservable<'a, Source, T, E> {
+        DematerializeObservable {
+            source: source,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        }
+    }
+}
+
+impl<'a, Source, T, E> Observable for DematerializeObservable<'a, Source, T, E>
+where Source: Observable<Item = Notification<T, E>, Error = ()>,
+      T: Clone,
+      E: Clone {
+    type Item = T;
+    type Error = E;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let dematerialize_observer = DematerializeObserver {
+            observer: Some(observer),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(dematerialize_observer)
+    }
+}
+
+/// The subscription to an `amb()` observable.
+pub struct AmbSubscription<Source: Observable, ObOther: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: lifeline::Lifeline<Option<Source::Subscription>>,
+
+    #[allow(dead_code)] // Same here.
+    subs_other: lifeline::Lifeline<Option<ObOther::Subscription>>,
+}
+
+impl<Source: Observable, ObOther: Observable> Subscription for AmbSubscription<Source, ObOther> { }
+
+/// State shared by the two observers of an `amb()` observable.
+struct AmbState<O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// Whether one of the sources has won already.
+    decided: bool,
+}
+
+struct AmbObserver<T, E, O, S>
+where O: Observer<T, E> {
+    state: Rc<RefCell<AmbState<O>>>,
+    won: bool,
+    /// Drops the subscription to the other source when this source wins.
+    unsubscriber: Unsubscriber<S>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> AmbObserver<T, E, O, S>
+where O: Observer<T, E> {
+    /// Returns whether this source won, making it the winner if it is first.
+    fn claim(&mut self) -> bool {
+        if self.won {
+            return true
+        }
+
+        let lost = {
+            let mut state = self.state.borrow_mut();
+            let lost = state.decided;
+            state.decided = true;
+            lost
+        };
+
+        if !lost {
+            self.won = true;
+            self.unsubscriber.unsubscribe();
+        }
+        self.won
+    }
+}
+
+impl<T, E, O, S> Observer<T, E> for AmbObserver<T, E, O, S>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if self.claim() {
+            if let Some(ref mut observer) = self.state.borrow_mut().observer {
+                observer.on_next(item);
+            }
+        }
+    }
+
+    fn on_completed(mut self) {
+        if self.claim() {
+            if let Some(observer) = self.state.borrow_mut().observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn on_error(mut self, error: E) {
+        if self.claim() {
+            if let Some(observer) = self.state.borrow_mut().observer.take() {
+                observer.on_error(error);
+            }
+        }
+    }
+}
+
+/// The result of calling `amb()` on an observable.
+pub struct AmbObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> {
+    source: &'a mut Source,
+    other: &'a mut ObOther,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> AmbObservable<'a, Source, ObOther> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther) -> AmbObservable<'a, Source, ObOther> {
+        AmbObservable {
+            source: source,
+            other: other,
+        }
+    }
+}
+
+impl<'a, T: Clone, E: Clone, Source, ObOther> Observable for AmbObservable<'a, Source, ObOther>
+where Source: Observable<Item = T, Error = E>,
+      ObOther: Observable<Item = T, Error = E> {
+    type Item = T;
+    type Error = E;
+    type Subscription = AmbSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(AmbState {
+            observer: Some(observer),
+            decided: false,
+        }));
+        let (mut life_source, owner_source) = lifeline::new(None);
+        let (mut life_other, owner_other) = lifeline::new(None);
+        let source_observer = AmbObserver {
+            state: state.clone(),
+            won: false,
+            unsubscriber: Unsubscriber { owner: Some(owner_other) },
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let other_observer = AmbObserver {
+            state: state,
+            won: false,
+            unsubscriber: Unsubscriber { owner: Some(owner_source) },
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+
+        // If a source lost already, its owner took the value out of the
+        // lifeline, and its subscription is dropped here.
+        let subs_source = self.source.subscribe(source_observer);
+        life_source.with_mut_value(move |subs| *subs = Some(subs_source));
+
+        // If the source already won during subscription, it took the value out
+        // of the other lifeline, and there is no need to subscribe to the other
+        // observable at all.
+        if !life_other.is_expired() {
+            let subs_other = self.other.subscribe(other_observer);
+            life_other.with_mut_value(move |subs| *subs = Some(subs_other));
+        }
+
+        AmbSubscription {
+            subs_source: life_source,
+            subs_other: life_other,
+        }
+    }
+}
+
+pub struct TakeUntilSubscription<Source: Observable, ObStop: Observable> {
+    #[allow(dead_code)] // This code is not dead, it keeps the subscription alive.
+    subs_source: lifeline::Lifeline<Option<Source::Subscription>>,
+
+    #[allow(dead_code)] // Same here.
+    subs_stop: ObStop::Subscription,
+}
+
+impl<Source: Observable, ObStop: Observable> Subscription for TakeUntilSubscription<Source, ObStop> { }
+
+struct TakeUntilObserver<T, E, O>
+where O: Observer<T, E> {
+    observer: Rc<RefCell<Option<O>>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for TakeUntilObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        if let Some(ref mut observer) = *self.observer.borrow_mut() {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.observer.borrow_mut().take() {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer.borrow_mut().take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct TakeUntilStopObserver<T, E, O, S>
+where O: Observer<T, E> {
+    observer: Rc<RefCell<Option<O>>>,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> TakeUntilStopObserver<T, E, O, S>
+where O: Observer<T, E> {
+    /// Completes the observer and drops the subscription to the source.
+    fn stop(&mut self) {
+        if let Some(observer) = self.observer.borrow_mut().take() {
+            observer.on_completed();
+        }
+        self.unsubscriber.unsubscribe();
+    }
+}
+
+impl<T, U, E, O, S> Observer<U, E> for TakeUntilStopObserver<T, E, O, S>
+where O: Observer<T, E> {
+    fn on_next(&mut self, _item: U) {
+        self.stop();
+    }
+
+    fn on_completed(mut self) {
+        self.stop();
+    }
+
+    fn on_error(mut self, error: E) {
+        if let Some(observer) = self.observer.borrow_mut().take() {
+            observer.on_error(error);
+        }
+        self.unsubscriber.unsubscribe();
+    }
+}
+
+/// The result of calling `take_until()` on an observable.
+pub struct TakeUntilObservable<'a, Source: 'a + ?Sized, ObStop: 'a + ?Sized> {
+    source: &'a mut Source,
+    stop: &'a mut ObStop,
+}
+
+impl<'a, Source: 'a + ?Sized, ObStop: 'a + ?Sized> TakeUntilObservable<'a, Source, ObStop> {
+    pub fn new(source: &'a mut Source, stop: &'a mut ObStop) -> TakeUntilObservable<'a, Source, ObStop> {
+        TakeUntilObservable {
+            source: source,
+            stop: stop,
+        }
+    }
+}
+
+impl<'a, Source, ObStop> Observable for TakeUntilObservable<'a, Source, ObStop>
+where Source: Observable,
+      ObStop: Observable<Error = <Source as Observable>::Error> {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = TakeUntilSubscription<Source, ObStop>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let observer = Rc::new(RefCell::new(Some(observer)));
+        let (mut life, owner) = lifeline::new(None);
+        let stop_observer = TakeUntilStopObserver {
+            observer: observer.clone(),
+            unsubscriber: Unsubscriber { owner: Some(owner) },
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let source_observer = TakeUntilObserver {
+            observer: observer,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+
+        // The stop observable is subscribed to first, so it can stop the
+        // source before it pushes its first value. If it stopped already, the
+        // subscription to the source is dropped as soon as it is stored.
+        let subs_stop = self.stop.subscribe(stop_observer);
+        let subs_source = self.source.subscribe(source_observer);
+        life.with_mut_value(move |subs| *subs = Some(subs_source));
+        TakeUntilSubscription {
+            subs_source: life,
+            subs_stop: subs_stop,
+        }
+    }
+}
+
+/// State shared by the two observers of a `skip_until()` observable.
+struct SkipUntilState<O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// Whether the gate pushed a value, after which values are passed through.
+    open: bool,
+}
+
+struct SkipUntilObserver<T, E, O>
+where O: Observer<T, E> {
+    state: Rc<RefCell<SkipUntilState<O>>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for SkipUntilObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        let mut state = self.state.borrow_mut();
+        if !state.open {
+            return;
+        }
+        if let Some(ref mut observer) = state.observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct SkipUntilGateObserver<T, E, O>
+where O: Observer<T, E> {
+    state: Rc<RefCell<SkipUntilState<O>>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, U, E, O> Observer<U, E> for SkipUntilGateObserver<T, E, O>
+where O: Observer<T, E> {
+    fn on_next(&mut self, _item: U) {
+        self.state.borrow_mut().open = true;
+    }
+
+    fn on_completed(self) {
+        // Completion of the gate does not affect the observer. If the gate
+        // did not open, no values will be passed through.
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `skip_until()` on an observable.
+pub struct SkipUntilObservable<'a, Source: 'a + ?Sized, ObGate: 'a + ?Sized> {
+    source: &'a mut Source,
+    gate: &'a mut ObGate,
+}
+
+impl<'a, Source: 'a + ?Sized, ObGate: 'a + ?Sized> SkipUntilObservable<'a, Source, ObGate> {
+    pub fn new(source: &'a mut Source, gate: &'a mut ObGate) -> SkipUntilObservable<'a, Source, ObGate> {
+        SkipUntilObservable {
+            source: source,
+            gate: gate,
+        }
+    }
+}
+
+impl<'a, Source, ObGate> Observable for SkipUntilObservable<'a, Source, ObGate>
+where Source: Observable,
+      ObGate: Observable<Error = <Source as Observable>::Error> {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = PairSubscription<Source, ObGate>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(SkipUntilState {
+            observer: Some(observer),
+            open: false,
+        }));
+        let gate_observer = SkipUntilGateObserver {
+            state: state.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let source_observer = SkipUntilObserver {
+            state: state,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+
+        // The gate is subscribed to first, so it can open before the source
+        // pushes its first value.
+        let subs_other = self.gate.subscribe(gate_observer);
+        let subs_source = self.source.subscribe(source_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+/// State shared by the two observers of a `sequence_equal()` observable.
+struct SequenceEqualState<T, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// Values of either source that have not been compared yet.
+    queues: [VecDeque<T>; 2],
+
+    /// Whether either source completed.
+    completed: [bool; 2],
+}
+
+impl<T: PartialEq, O> SequenceEqualState<T, O> {
+    /// Compares values while both queues are non-empty, and pushes the result
+    /// as soon as it is known.
+    fn compare<E>(&mut self) where O: Observer<bool, E> {
+        let mut equal = true;
+        while equal && !self.queues[0].is_empty() && !self.queues[1].is_empty() {
+            let a = self.queues[0].pop_front().unwrap();
+            let b = self.queues[1].pop_front().unwrap();
+            equal = a == b;
+        }
+
+        // If one source completed while the other one still has values left,
+        // the lengths differ.
+        let first_short = self.completed[0] && self.queues[0].is_empty() && !self.queues[1].is_empty();
+        let second_short = self.completed[1] && self.queues[1].is_empty() && !self.queues[0].is_empty();
+        let both_done = self.completed[0] && self.completed[1];
+
+        let result = if !equal || first_short || second_short {
+            Some(false)
+        } else if both_done {
+            Some(true)
+        } else {
+            None
+        };
+
+        if let Some(result) = result {
+            if let Some(mut observer) = self.observer.take() {
+                observer.on_next(result);
+                observer.on_completed();
+            }
+        }
+    }
+}
+
+struct SequenceEqualObserver<T, E, O>
+where O: Observer<bool, E> {
+    state: Rc<RefCell<SequenceEqualState<T, O>>>,
+    /// The index of the queue of this observer's source.
+    index: usize,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for SequenceEqualObserver<T, E, O>
+where T: PartialEq,
+      O: Observer<bool, E> {
+    fn on_next(&mut self, item: T) {
+        let mut state = self.state.borrow_mut();
+        if state.observer.is_some() {
+            state.queues[self.index].push_back(item);
+            state.compare();
+        }
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.completed[self.index] = true;
+        state.compare();
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `sequence_equal()` on an observable.
+pub struct SequenceEqualObservable<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> {
+    source: &'a mut Source,
+    other: &'a mut ObOther,
+}
+
+impl<'a, Source: 'a + ?Sized, ObOther: 'a + ?Sized> SequenceEqualObservable<'a, Source, ObOther> {
+    pub fn new(source: &'a mut Source, other: &'a mut ObOther) -> SequenceEqualObservable<'a, Source, ObOther> {
+        SequenceEqualObservable {
+            source: source,
+            other: other,
+        }
+    }
+}
+
+impl<'a, T, E: Clone, Source, ObOther> Observable for SequenceEqualObservable<'a, Source, ObOther>
+where T: Clone + PartialEq,
+      Source: Observable<Item = T, Error = E>,
+      ObOther: Observable<Item = T, Error = E> {
+    type Item = bool;
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObOther>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(SequenceEqualState {
+            observer: Some(observer),
+            queues: [VecDeque::new(), VecDeque::new()],
+            completed: [false, false],
+        }));
+        let first_observer = SequenceEqualObserver {
+            state: state.clone(),
+            index: 0,
+            _phantom_e: PhantomData,
+        };
+        let second_observer = SequenceEqualObserver {
+            state: state,
+            index: 1,
+            _phantom_e: PhantomData,
+        };
+        let subs_source = self.source.subscribe(first_observer);
+        let subs_other = self.other.subscribe(second_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+struct ContainsObserver<T, E, O, S>
+where O: Observer<bool, E> {
+    observer: Option<O>,
+    target: T,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, S> Observer<T, E> for ContainsObserver<T, E, O, S>
+where T: Clone + PartialEq,
+      E: Clone,
+      O: Observer<bool, E> {
+    fn on_next(&mut self, item: T) {
+        if self.observer.is_some() && item == self.target {
+            if let Some(mut observer) = self.observer.take() {
+                observer.on_next(true);
+                observer.on_completed();
+                self.unsubscriber.unsubscribe();
+            }
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(mut observer) = self.observer {
+            observer.on_next(false);
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `contains()` on an observable.
+pub struct ContainsObservable<'a, Source: 'a + Observable + ?Sized> {
+    source: &'a mut Source,
+    target: Source::Item,
+}
+
+impl<'a, Source: 'a + Observable + ?Sized> ContainsObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, target: Source::Item) -> ContainsObservable<'a, Source> {
+        ContainsObservable {
+            source: source,
+            target: target,
+        }
+    }
+}
+
+impl<'a, Source> Observable for ContainsObservable<'a, Source>
+where Source: Observable,
+      <Source as Observable>::Item: PartialEq {
+    type Item = bool;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ShortCircuitSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let target = self.target.clone();
+        ShortCircuitSubscription::subscribe(self.source, |unsubscriber| ContainsObserver {
+            observer: Some(observer),
+            target: target,
+            unsubscriber: unsubscriber,
+            _phantom_e: PhantomData,
+        })
+    }
+}
+
+struct SkipLastObserver<T, E, O>
+where O: Observer<T, E> {
+    observer: O,
+    n: usize,
+    // The most recent `n` values, which might be among the last `n`.
+    buffer: VecDeque<T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for SkipLastObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        self.buffer.push_back(item);
+        if self.buffer.len() > self.n {
+            let oldest = self.buffer.pop_front().unwrap();
+            self.observer.on_next(oldest);
+        }
+    }
+
+    fn on_completed(self) {
+        // The buffered values are the last `n`, they are discarded.
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `skip_last()` on an observable.
+pub struct SkipLastObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    n: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> SkipLastObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, n: usize) -> SkipLastObservable<'a, Source> {
+        SkipLastObservable {
+            source: source,
+            n: n,
+        }
+    }
+}
+
+impl<'a, Source> Observable for SkipLastObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let skip_last_observer = SkipLastObserver {
+            observer: observer,
+            n: self.n,
+            buffer: VecDeque::with_capacity(self.n + 1),
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(skip_last_observer)
+    }
+}
+
+/// A group of values with the same key, part of the item of a `group_by()` observable.
+///
+/// A group is an observable that pushes the values of the source observable
+/// that have its key. Like a window, values are only pushed to observers that
+/// subscribed before the value was produced.
+#[derive(Clone)]
+pub struct GroupedObservable<T, E> {
+    subject: SubjectHandle<T, E>,
+}
+
+impl<T: Clone, E: Clone> Observable for GroupedObservable<T, E> {
+    type Item = T;
+    type Error = E;
+    type Subscription = SubjectSubscription<T, E>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.subject.subscribe(observer)
+    }
+}
+
+struct GroupByObserver<T, E, K, O, F>
+where O: Observer<(K, GroupedObservable<T, E>), E>,
+      K: Eq + Hash,
+      F: Fn(&T) -> K {
+    observer: O,
+    key: F,
+    groups: HashMap<K, Subject<T, E>>,
+}
+
+impl<T, E, K, O, F> Observer<T, E> for GroupByObserver<T, E, K, O, F>
+where T: Clone,
+      E: Clone,
+      K: Clone + Eq + Hash,
+      O: Observer<(K, GroupedObservable<T, E>), E>,
+      F: Fn(&T) -> K {
+    fn on_next(&mut self, item: T) {
+        let key = self.key.call((&item,));
+        if !self.groups.contains_key(&key) {
+            // Push the new group first so the observer can subscribe to it
+            // before it receives the value.
+            let subject = Subject::new();
+            let group = GroupedObservable { subject: subject.handle() };
+            self.groups.insert(key.clone(), subject);
+            self.observer.on_next((key.clone(), group));
+        }
+
+        if let Some(subject) = self.groups.get_mut(&key) {
+            subject.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        for (_key, subject) in self.groups {
+            subject.on_completed();
+        }
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        for (_key, subject) in self.groups {
+            subject.on_error(error.clone());
+        }
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `group_by()` on an observable.
+pub struct GroupByObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    key: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> GroupByObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, key: F) -> GroupByObservable<'a, Source, F> {
+        GroupByObservable {
+            source: source,
+            key: key,
+        }
+    }
+}
+
+impl<'a, Source, K, F> Observable for GroupByObservable<'a, Source, F>
+where Source: Observable,
+      K: Clone + Eq + Hash,
+      F: Fn(&<Source as Observable>::Item) -> K {
+    type Item = (K, GroupedObservable<<Source as Observable>::Item, <Source as Observable>::Error>);
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let group_by_observer = GroupByObserver {
+            observer: observer,
+            key: &self.key,
+            groups: HashMap::new(),
+        };
+        self.source.subscribe(group_by_observer)
+    }
+}
+
+struct DelayByCountObserver<T, E, O>
+where O: Observer<T, E> {
+    observer: O,
+    n: usize,
+    // The most recent `n` values, which have not been pushed yet.
+    buffer: VecDeque<T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for DelayByCountObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        self.buffer.push_back(item);
+        if self.buffer.len() > self.n {
+            let oldest = self.buffer.pop_front().unwrap();
+            self.observer.on_next(oldest);
+        }
+    }
+
+    fn on_completed(mut self) {
+        for item in self.buffer.drain(..) {
+            self.observer.on_next(item);
+        }
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        // The buffered values are discarded, the error is not delayed.
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `delay_by_count()` on an observable.
+pub struct DelayByCountObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+    n: usize,
+}
+
+impl<'a, Source: 'a + ?Sized> DelayByCountObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, n: usize) -> DelayByCountObservable<'a, Source> {
+        DelayByCountObservable {
+            source: source,
+            n: n,
+        }
+    }
+}
+
+impl<'a, Source> Observable for DelayByCountObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let delay_observer = DelayByCountObserver {
+            observer: observer,
+            n: self.n,
+            buffer: VecDeque::with_capacity(self.n + 1),
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(delay_observer)
+    }
+}
+
+struct IndexIntervalObserver<T, E, O, F>
+where O: Observer<(T, usize), E>,
+      F: Fn(usize, &T) -> usize {
+    observer: O,
+    position: F,
+    // The number of values received so far.
+    count: usize,
+    // The position of the previous value, if any.
+    previous: Option<usize>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, F> Observer<T, E> for IndexIntervalObserver<T, E, O, F>
+where T: Clone,
+      E: Clone,
+      O: Observer<(T, usize), E>,
+      F: Fn(usize, &T) -> usize {
+    fn on_next(&mut self, item: T) {
+        let position = self.position.call((self.count, &item));
+        let interval = match self.previous {
+            Some(previous) => position.saturating_sub(previous),
+            None => 0,
+        };
+        self.count += 1;
+        self.previous = Some(position);
+        self.observer.on_next((item, interval));
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.observer.on_error(error);
+    }
+}
+
+/// The result of calling `index_interval_by()` on an observable.
+pub struct IndexIntervalByObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    position: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> IndexIntervalByObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, position: F) -> IndexIntervalByObservable<'a, Source, F> {
+        IndexIntervalByObservable {
+            source: source,
+            position: position,
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for IndexIntervalByObservable<'a, Source, F>
+where Source: Observable,
+      F: Fn(usize, &<Source as Observable>::Item) -> usize {
+    type Item = (<Source as Observable>::Item, usize);
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let interval_observer = IndexIntervalObserver {
+            observer: observer,
+            position: &self.position,
+            count: 0,
+            previous: None,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(interval_observer)
+    }
+}
+
+/// The result of calling `index_interval()` on an observable.
+pub type IndexIntervalObservable<'a, Source> = IndexIntervalByObservable<'a, Source,
+    fn(usize, &<Source as Observable>::Item) -> usize>;
+
+/// Returns the index at which a value arrived, ignoring the value itself.
+pub(crate) fn arrival_index<T>(index: usize, _item: &T) -> usize {
+    index
+}
+
+/// The result of calling `publish()` on an observable.
+pub struct ConnectableObservable<'a, Source: 'a + Observable + ?Sized> {
+    source: &'a mut Source,
+    /// The subject that the source pushes into, until it is connected.
+    subject: Option<Subject<Source::Item, Source::Error>>,
+    handle: SubjectHandle<Source::Item, Source::Error>,
+}
+
+impl<'a, Source: 'a + Observable + ?Sized> ConnectableObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> ConnectableObservable<'a, Source> {
+        let subject = Subject::new();
+        let handle = subject.handle();
+        ConnectableObservable {
+            source: source,
+            subject: Some(subject),
+            handle: handle,
+        }
+    }
+
+    /// Subscribes to the source, so its values are pushed to the observers.
+    ///
+    /// Dropping the returned subscription disconnects from the source.
+    ///
+    /// Panics if the observable was connected before.
+    pub fn connect(&mut self) -> Source::Subscription {
+        let subject = self.subject.take().expect("connect() called on connected observable");
+        self.source.subscribe(subject)
+    }
+}
+
+impl<'a, Source> Observable for ConnectableObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = SubjectSubscription<Self::Item, Self::Error>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        self.handle.subscribe(observer)
+    }
+}
+
+struct OnErrorReturnObserver<T, E, O, F>
+where O: Observer<T, E>,
+      F: Fn(E) -> T {
+    observer: O,
+    f: F,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O, F> Observer<T, E> for OnErrorReturnObserver<T, E, O, F>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E>,
+      F: Fn(E) -> T {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        let OnErrorReturnObserver { mut observer, f, .. } = self;
+        observer.on_next(f.call((error,)));
+        observer.on_completed();
+    }
+}
+
+/// The result of calling `on_error_return()` on an observable.
+pub struct OnErrorReturnObservable<'a, Source: 'a + Observable + ?Sized> {
+    source: &'a mut Source,
+    value: Source::Item,
+}
+
+impl<'a, Source: 'a + Observable + ?Sized> OnErrorReturnObservable<'a, Source> {
+    pub fn new(source: &'a mut Source, value: Source::Item) -> OnErrorReturnObservable<'a, Source> {
+        OnErrorReturnObservable {
+            source: source,
+            value: value,
+        }
+    }
+}
+
+impl<'a, Source> Observable for OnErrorReturnObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let value = &self.value;
+        let return_observer = OnErrorReturnObserver {
+            observer: observer,
+            f: move |_error| value.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(return_observer)
+    }
+}
+
+/// The result of calling `on_error_return_with()` on an observable.
+pub struct OnErrorReturnWithObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> OnErrorReturnWithObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> OnErrorReturnWithObservable<'a, Source, F> {
+        OnErrorReturnWithObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, F> Observable for OnErrorReturnWithObservable<'a, Source, F>
+where Source: Observable,
+      F: Fn(<Source as Observable>::Error) -> <Source as Observable>::Item {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let return_observer = OnErrorReturnObserver {
+            observer: observer,
+            f: &self.f,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(return_observer)
+    }
+}
+
+struct OnErrorCompleteObserver<T, E, O>
+where O: Observer<T, E> {
+    observer: O,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for OnErrorCompleteObserver<T, E, O>
+where T: Clone,
+      E: Clone,
+      O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        self.observer.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.observer.on_completed();
+    }
+
+    fn on_error(self, _error: E) {
+        self.observer.on_completed();
+    }
+}
+
+/// The result of calling `on_error_complete()` on an observable.
+pub struct OnErrorCompleteObservable<'a, Source: 'a + ?Sized> {
+    source: &'a mut Source,
+}
+
+impl<'a, Source: 'a + ?Sized> OnErrorCompleteObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> OnErrorCompleteObservable<'a, Source> {
+        OnErrorCompleteObservable {
+            source: source,
+        }
+    }
+}
+
+impl<'a, Source> Observable for OnErrorCompleteObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = <Source as Observable>::Subscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let complete_observer = OnErrorCompleteObserver {
+            observer: observer,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        self.source.subscribe(complete_observer)
+    }
+}
+
+/// State shared by the two observers of a `buffer_boundary()` observable.
+struct BufferBoundaryState<T, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// The values produced since the previous boundary.
+    buffer: Vec<T>,
+}
+
+struct BufferBoundaryObserver<T, E, O>
+where O: Observer<Vec<T>, E> {
+    state: Rc<RefCell<BufferBoundaryState<T, O>>>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for BufferBoundaryObserver<T, E, O>
+where O: Observer<Vec<T>, E> {
+    fn on_next(&mut self, item: T) {
+        self.state.borrow_mut().buffer.push(item);
+    }
+
+    fn on_completed(self) {
+        use std::mem;
+        let mut state = self.state.borrow_mut();
+        let buffer = mem::take(&mut state.buffer);
+        if let Some(mut observer) = state.observer.take() {
+            if !buffer.is_empty() {
+                observer.on_next(buffer);
+            }
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct BufferBoundarySignalObserver<T, U, E, O>
+where O: Observer<Vec<T>, E> {
+    state: Rc<RefCell<BufferBoundaryState<T, O>>>,
+    _phantom_u: PhantomData<*mut U>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, U, E, O> Observer<U, E> for BufferBoundarySignalObserver<T, U, E, O>
+where O: Observer<Vec<T>, E> {
+    fn on_next(&mut self, _item: U) {
+        use std::mem;
+        let mut state = self.state.borrow_mut();
+        let BufferBoundaryState { ref mut observer, ref mut buffer } = *state;
+        if let Some(ref mut observer) = *observer {
+            observer.on_next(mem::take(buffer));
+        }
+    }
+
+    fn on_completed(self) {
+        // Without boundaries, the values are pushed when the source completes.
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `buffer_boundary()` on an observable.
+pub struct BufferBoundaryObservable<'a, Source: 'a + ?Sized, ObSignal: 'a + ?Sized> {
+    source: &'a mut Source,
+    boundary: &'a mut ObSignal,
+}
+
+impl<'a, Source: 'a + ?Sized, ObSignal: 'a + ?Sized> BufferBoundaryObservable<'a, Source, ObSignal> {
+    pub fn new(source: &'a mut Source, boundary: &'a mut ObSignal) -> BufferBoundaryObservable<'a, Source, ObSignal> {
+        BufferBoundaryObservable {
+            source: source,
+            boundary: boundary,
+        }
+    }
+}
+
+impl<'a, E: Clone, Source, ObSignal> Observable for BufferBoundaryObservable<'a, Source, ObSignal>
+where Source: Observable<Error = E>,
+      ObSignal: Observable<Error = E> {
+    type Item = Vec<<Source as Observable>::Item>;
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObSignal>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(BufferBoundaryState {
+            observer: Some(observer),
+            buffer: Vec::new(),
+        }));
+        let source_observer = BufferBoundaryObserver {
+            state: state.clone(),
+            _phantom_e: PhantomData,
+        };
+        let signal_observer = BufferBoundarySignalObserver {
+            state: state,
+            _phantom_u: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let subs_other = self.boundary.subscribe(signal_observer);
+        let subs_source = self.source.subscribe(source_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+/// State shared by the two observers of a `pausable()` observable.
+struct PausableState<O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// Whether values are passed through, the most recent control value.
+    open: bool,
+}
+
+struct PausableObserver<T, E, O>
+where O: Observer<T, E> {
+    state: Rc<RefCell<PausableState<O>>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<T, E> for PausableObserver<T, E, O>
+where O: Observer<T, E> {
+    fn on_next(&mut self, item: T) {
+        let mut state = self.state.borrow_mut();
+        if state.open {
+            if let Some(ref mut observer) = state.observer {
+                observer.on_next(item);
+            }
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct PausableControlObserver<T, E, O>
+where O: Observer<T, E> {
+    state: Rc<RefCell<PausableState<O>>>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, E, O> Observer<bool, E> for PausableControlObserver<T, E, O>
+where O: Observer<T, E> {
+    fn on_next(&mut self, open: bool) {
+        self.state.borrow_mut().open = open;
+    }
+
+    fn on_completed(self) {
+        // The most recent control value remains in effect.
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `pausable()` on an observable.
+pub struct PausableObservable<'a, Source: 'a + ?Sized, ObControl: 'a + ?Sized> {
+    source: &'a mut Source,
+    control: &'a mut ObControl,
+    initial: bool,
+}
+
+impl<'a, Source: 'a + ?Sized, ObControl: 'a + ?Sized> PausableObservable<'a, Source, ObControl> {
+    pub fn new(source: &'a mut Source,
+               control: &'a mut ObControl,
+               initial: bool)
+               -> PausableObservable<'a, Source, ObControl> {
+        PausableObservable {
+            source: source,
+            control: control,
+            initial: initial,
+        }
+    }
+}
+
+impl<'a, E: Clone, Source, ObControl> Observable for PausableObservable<'a, Source, ObControl>
+where Source: Observable<Error = E>,
+      ObControl: Observable<Item = bool, Error = E> {
+    type Item = <Source as Observable>::Item;
+    type Error = E;
+    type Subscription = PairSubscription<Source, ObControl>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(PausableState {
+            observer: Some(observer),
+            open: self.initial,
+        }));
+        let source_observer = PausableObserver {
+            state: state.clone(),
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+        let control_observer = PausableControlObserver {
+            state: state,
+            _phantom_t: PhantomData,
+            _phantom_e: PhantomData,
+        };
+
+        // Subscribe to the control observable first, so a value that it
+        // pushes upon subscription applies to the values of the source.
+        let subs_other = self.control.subscribe(control_observer);
+        let subs_source = self.source.subscribe(source_observer);
+        PairSubscription {
+            subs_source: subs_source,
+            subs_other: subs_other,
+        }
+    }
+}
+
+struct TryMapObserver<T, U, E, O, F, S>
+where O: Observer<U, E>,
+      F: Fn(T) -> Result<U, E> {
+    observer: Option<O>,
+    f: F,
+    unsubscriber: Unsubscriber<S>,
+    _phantom_t: PhantomData<*mut T>,
+    _phantom_u: PhantomData<*mut U>,
+    _phantom_e: PhantomData<*mut E>,
+}
+
+impl<T, U, E, O, F, S> Observer<T, E> for TryMapObserver<T, U, E, O, F, S>
+where O: Observer<U, E>,
+      F: Fn(T) -> Result<U, E> {
+    fn on_next(&mut self, item: T) {
+        if self.observer.is_none() {
+            return;
+        }
+        match self.f.call((item,)) {
+            Ok(value) => {
+                if let Some(ref mut observer) = self.observer {
+                    observer.on_next(value);
+                }
+            }
+            Err(error) => {
+                if let Some(observer) = self.observer.take() {
+                    observer.on_error(error);
+                }
+                self.unsubscriber.unsubscribe();
+            }
+        }
+    }
+
+    fn on_completed(self) {
+        if let Some(observer) = self.observer {
+            observer.on_completed();
+        }
+    }
+
+    fn on_error(self, error: E) {
+        if let Some(observer) = self.observer {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `try_map()` on an observable.
+pub struct TryMapObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> TryMapObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, f: F) -> TryMapObservable<'a, Source, F> {
+        TryMapObservable {
+            source: source,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, U, F> Observable for TryMapObservable<'a, Source, F>
+where Source: Observable,
+      U: Clone,
+      F: Fn(<Source as Observable>::Item) -> Result<U, <Source as Observable>::Error> {
+    type Item = U;
+    type Error = <Source as Observable>::Error;
+    type Subscription = ShortCircuitSubscription<Source>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let f = &self.f;
+        ShortCircuitSubscription::subscribe(self.source, |unsubscriber| TryMapObserver {
+            observer: Some(observer),
+            f: f,
+            unsubscriber: unsubscriber,
+            _phantom_t: PhantomData,
+            _phantom_u: PhantomData,
+            _phantom_e: PhantomData,
+        })
+    }
+}
+
+/// State shared by a cached observable and the observer of its source.
+struct CacheState<T, E, S> {
+    /// Everything the source pushed so far.
+    notifications: Vec<Notification<T, E>>,
+    /// The subject that pushes new notifications, after the first subscription.
+    handle: Option<SubjectHandle<T, E>>,
+    subs_source: Option<S>,
+}
+
+struct CacheObserver<T, E, S> {
+    subject: Subject<T, E>,
+    // This is a weak reference, because the state owns the subscription to
+    // the source, which might own this observer.
+    state: Weak<RefCell<CacheState<T, E, S>>>,
+}
+
+impl<T, E, S> CacheObserver<T, E, S> {
+    /// Records the notification, so later observers can replay it.
+    fn record(&self, notification: Notification<T, E>) {
+        if let Some(state) = self.state.upgrade() {
+            state.borrow_mut().notifications.push(notification);
+        }
+    }
+}
+
+impl<T, E, S> Observer<T, E> for CacheObserver<T, E, S>
+where T: Clone,
+      E: Clone {
+    fn on_next(&mut self, item: T) {
+        self.record(Notification::Next(item.clone()));
+        self.subject.on_next(item);
+    }
+
+    fn on_completed(self) {
+        self.record(Notification::Completed);
+        self.subject.on_completed();
+    }
+
+    fn on_error(self, error: E) {
+        self.record(Notification::Error(error.clone()));
+        self.subject.on_error(error);
+    }
+}
+
+/// The subscription to a cached observable.
+pub struct CacheSubscription<T, E> {
+    #[allow(dead_code)] // This code is not dead, it keeps the observer subscribed.
+    subs_subject: Option<SubjectSubscription<T, E>>,
+}
+
+impl<T, E> Subscription for CacheSubscription<T, E> { }
+
+/// The cache state for the items, errors and subscription of a source.
+type CachedState<Source> = Rc<RefCell<CacheState<<Source as Observable>::Item,
+                                                 <Source as Observable>::Error,
+                                                 <Source as Observable>::Subscription>>>;
+
+/// The result of calling `cache()` on an observable.
+pub struct CacheObservable<'a, Source: 'a + Observable + ?Sized> {
+    source: &'a mut Source,
+    state: CachedState<Source>,
+}
+
+impl<'a, Source: 'a + Observable + ?Sized> CacheObservable<'a, Source> {
+    pub fn new(source: &'a mut Source) -> CacheObservable<'a, Source> {
+        let state = CacheState {
+            notifications: Vec::new(),
+            handle: None,
+            subs_source: None,
+        };
+        CacheObservable {
+            source: source,
+            state: Rc::new(RefCell::new(state)),
+        }
+    }
+}
+
+impl<'a, Source> Observable for CacheObservable<'a, Source>
+where Source: Observable {
+    type Item = <Source as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = CacheSubscription<Self::Item, Self::Error>;
+
+    fn subscribe<O>(&mut self, mut observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let (notifications, handle) = {
+            let state = self.state.borrow();
+            (state.notifications.clone(), state.handle.clone())
+        };
+
+        let mut handle = match handle {
+            Some(handle) => handle,
+            None => {
+                // This is the first subscription, connect to the source.
+                let subject = Subject::new();
+                let mut handle = subject.handle();
+                self.state.borrow_mut().handle = Some(subject.handle());
+                let subs_subject = handle.subscribe(observer);
+                let cache_observer = CacheObserver {
+                    subject: subject,
+                    state: Rc::downgrade(&self.state),
+                };
+                let subs_source = self.source.subscribe(cache_observer);
+                self.state.borrow_mut().subs_source = Some(subs_source);
+                return CacheSubscription {
+                    subs_subject: Some(subs_subject),
+                };
+            }
+        };
+
+        // Replay the notifications pushed so far. The state must not be
+        // borrowed while the observer is invoked.
+        for notification in notifications {
+            match notification {
+                Notification::Next(item) => observer.on_next(item),
+                Notification::Completed => {
+                    observer.on_completed();
+                    return CacheSubscription { subs_subject: None };
+                }
+                Notification::Error(error) => {
+                    observer.on_error(error);
+                    return CacheSubscription { subs_subject: None };
+                }
+            }
+        }
+
+        CacheSubscription {
+            subs_subject: Some(handle.subscribe(observer)),
+        }
+    }
+}
+
+/// State shared by the observers of a flat-mapped observable with a limit.
+struct FlatMapLimitState<T, O> {
+    /// The downstream observer, `None` after it completed or failed.
+    observer: Option<O>,
+
+    /// Values of the source for which no inner observable was subscribed yet.
+    pending: VecDeque<T>,
+
+    /// The number of inner observables subscribed that did not complete yet.
+    active: usize,
+
+    /// The maximum number of inner observables to subscribe at the same time.
+    max_active: usize,
+
+    /// Whether the source completed.
+    source_completed: bool,
+}
+
+/// Subscribes the observer to the inner observable for the value.
+///
+/// The state must not be borrowed when this is called.
+fn flat_map_limit_subscribe<'f, T, ObInner, O, F>(state: &Rc<RefCell<FlatMapLimitState<T, O>>>,
+                                                  f: &'f F,
+                                                  subscriptions: &lifeline::Owner<Vec<ObInner::Subscription>>,
+                                                  item: T)
+    where ObInner: Observable,
+          O: Observer<ObInner::Item, ObInner::Error>,
+          F: Fn(T) -> ObInner {
+    state.borrow_mut().active += 1;
+    let inner_observer = FlatMapLimitInnerObserver {
+        state: state.clone(),
+        f: f,
+        subscriptions: subscriptions.clone(),
+    };
+    let mut inner = f.call((item,));
+    let subs_inner = inner.subscribe(inner_observer);
+
+    // If the inner observable completes during subscription, the subscription
+    // of the next one is pushed first. The order does not matter, all
+    // subscriptions are kept alive until the flat-mapped subscription is
+    // dropped.
+    subscriptions.clone().with_mut_value(move |subs| subs.push(subs_inner));
+}
+
+struct FlatMapLimitObserver<'f, T, ObInner, O, F: 'f>
+where ObInner: Observable,
+      O: Observer<ObInner::Item, ObInner::Error>,
+      F: Fn(T) -> ObInner {
+    state: Rc<RefCell<FlatMapLimitState<T, O>>>,
+    f: &'f F,
+    subscriptions: lifeline::Owner<Vec<ObInner::Subscription>>,
+}
+
+impl<'f, T, ObInner, O, F> Observer<T, ObInner::Error> for FlatMapLimitObserver<'f, T, ObInner, O, F>
+where ObInner: Observable,
+      O: Observer<ObInner::Item, ObInner::Error>,
+      F: Fn(T) -> ObInner {
+    fn on_next(&mut self, item: T) {
+        {
+            let mut state = self.state.borrow_mut();
+            if state.observer.is_none() {
+                // An inner observable failed already, do not subscribe any more.
+                return;
+            }
+            if state.active == state.max_active {
+                state.pending.push_back(item);
+                return;
+            }
+        }
+        flat_map_limit_subscribe(&self.state, self.f, &self.subscriptions, item);
+    }
+
+    fn on_completed(self) {
+        let mut state = self.state.borrow_mut();
+        state.source_completed = true;
+        if state.active == 0 {
+            if let Some(observer) = state.observer.take() {
+                observer.on_completed();
+            }
+        }
+    }
+
+    fn on_error(self, error: ObInner::Error) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+struct FlatMapLimitInnerObserver<'f, T, ObInner, O, F: 'f>
+where ObInner: Observable,
+      O: Observer<ObInner::Item, ObInner::Error>,
+      F: Fn(T) -> ObInner {
+    state: Rc<RefCell<FlatMapLimitState<T, O>>>,
+    f: &'f F,
+    subscriptions: lifeline::Owner<Vec<ObInner::Subscription>>,
+}
+
+impl<'f, T, ObInner, O, F> Observer<ObInner::Item, ObInner::Error> for FlatMapLimitInnerObserver<'f, T, ObInner, O, F>
+where ObInner: Observable,
+      O: Observer<ObInner::Item, ObInner::Error>,
+      F: Fn(T) -> ObInner {
+    fn on_next(&mut self, item: ObInner::Item) {
+        if let Some(ref mut observer) = self.state.borrow_mut().observer {
+            observer.on_next(item);
+        }
+    }
+
+    fn on_completed(self) {
+        let next = {
+            let mut state = self.state.borrow_mut();
+            state.active -= 1;
+            match state.pending.pop_front() {
+                Some(item) => Some(item),
+                None => {
+                    if state.source_completed && state.active == 0 {
+                        if let Some(observer) = state.observer.take() {
+                            observer.on_completed();
+                        }
+                    }
+                    None
+                }
+            }
+        };
+        if let Some(item) = next {
+            flat_map_limit_subscribe(&self.state, self.f, &self.subscriptions, item);
+        }
+    }
+
+    fn on_error(self, error: ObInner::Error) {
+        if let Some(observer) = self.state.borrow_mut().observer.take() {
+            observer.on_error(error);
+        }
+    }
+}
+
+/// The result of calling `flat_map_limit()` on an observable.
+pub struct FlatMapLimitObservable<'a, Source: 'a + ?Sized, F> {
+    source: &'a mut Source,
+    max_concurrent: usize,
+    f: F,
+}
+
+impl<'a, Source: 'a + ?Sized, F> FlatMapLimitObservable<'a, Source, F> {
+    pub fn new(source: &'a mut Source, max_concurrent: usize, f: F) -> FlatMapLimitObservable<'a, Source, F> {
+        assert!(max_concurrent > 0, "concurrency limit must be at least 1");
+        FlatMapLimitObservable {
+            source: source,
+            max_concurrent: max_concurrent,
+            f: f,
+        }
+    }
+}
+
+impl<'a, Source, ObInner, F> Observable for FlatMapLimitObservable<'a, Source, F>
+where Source: Observable,
+      ObInner: Observable<Error = <Source as Observable>::Error>,
+      F: Fn(<Source as Observable>::Item) -> ObInner {
+    type Item = <ObInner as Observable>::Item;
+    type Error = <Source as Observable>::Error;
+    type Subscription = FlatMapSubscription<Source, ObInner>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<Self::Item, Self::Error> {
+        let state = Rc::new(RefCell::new(FlatMapLimitState {
+            observer: Some(observer),
+            pending: VecDeque::new(),
+            active: 0,
+            max_active: self.max_concurrent,
+            source_completed: false,
+        }));
+        let (life, owner) = lifeline::new(Vec::new());
+        let flat_map_observer = FlatMapLimitObserver {
+            state: state,
+            f: &self.f,
+            subscriptions: owner,
+        };
+        let subs_source = self.source.subscribe(flat_map_observer);
+        FlatMapSubscription {
+            subs_source: subs_source,
+            subs_inner: life,
+        }
+    }
+}
+
+/// The result of calling `concat_map()` on an observable.
+pub type ConcatMapObservable<'a, Source, F> = FlatMapLimitObservable<'a, Source, F>;
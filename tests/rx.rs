@@ -7,8 +7,12 @@
 
 extern crate rx;
 
-use rx::{Never, Observable, Observer, Subject};
-use std::cell::RefCell;
+use rx::{BehaviorSubject, BoxObservable, BoxedObserver, ImmutableObservable, Never};
+use rx::{Notification, Observable};
+use rx::{Observer, OnUnsubscribe, SingleError, Subject, SubjectHandle, Subscription};
+use rx::{ChannelObserver, concat, defer, map_observer, merge_all, repeat, unfold};
+use rx::{from_fn, from_map, from_vec, zip_all};
+use std::cell::{Cell, RefCell};
 use std::rc::Rc;
 
 // Generator tests
@@ -27,6 +31,114 @@ fn never() {
     // will.
 }
 
+#[test]
+fn repeat_value() {
+    let mut received = Vec::new();
+    let mut completed = false;
+    repeat(7u8, 3).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[7u8, 7, 7][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn repeat_value_zero_times() {
+    let mut completed = false;
+    repeat(7u8, 0).subscribe_completed(
+        |_x| panic!("repeating zero times should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn unfold_fibonacci() {
+    let mut fibonacci = unfold((0u32, 1u32, 0), |(a, b, n)| {
+        if n < 5 { Some((a, (b, a + b, n + 1))) } else { None }
+    });
+    let mut received = Vec::new();
+    let mut completed = false;
+    fibonacci.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[0u32, 1, 1, 2, 3][..], &received[..]);
+    assert!(completed);
+
+    // Subscribing again should start from the seed.
+    received.clear();
+    fibonacci.subscribe_next(|x| received.push(x));
+    assert_eq!(&[0u32, 1, 1, 2, 3][..], &received[..]);
+}
+
+#[test]
+fn defer_constructs_per_subscription() {
+    let mut n = 0u32;
+    let mut deferred = defer(|| {
+        n += 1;
+        Some(n)
+    });
+    let mut received = Vec::new();
+    deferred.subscribe_next(|x| received.push(x));
+    deferred.subscribe_next(|x| received.push(x));
+    assert_eq!(&[1u32, 2][..], &received[..]);
+}
+
+#[test]
+fn never_function() {
+    let mut never = rx::never::<u8, ()>();
+    let subscription = never.subscribe_error(
+        |_x| panic!("never observable should not produce a value"),
+        || panic!("never observable should not complete"),
+        |_err| panic!("never observable should not fail")
+    );
+    subscription.unsubscribe();
+}
+
+#[test]
+fn merge_with_never() {
+    let mut values = from_vec(vec![2u8, 3, 5]);
+    let mut other = rx::never();
+    let mut received = Vec::new();
+    values.merge(&mut other).subscribe_completed(
+        |x| received.push(x),
+        || panic!("merge with never should not complete")
+    );
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+}
+
+#[test]
+fn from_fn_counts_down() {
+    let mut n = 3u8;
+    let mut countdown = from_fn(|| if n == 0 { None } else { n -= 1; Some(n + 1) });
+    let mut received = Vec::new();
+    let mut completed = false;
+    countdown.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[3u8, 2, 1][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn from_btree_map() {
+    use std::collections::BTreeMap;
+    let mut map = BTreeMap::new();
+    map.insert(5u8, "five");
+    map.insert(2, "two");
+    map.insert(3, "three");
+    let mut received = Vec::new();
+    let mut completed = false;
+    from_map(&map).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[(2u8, "two"), (3, "three"), (5, "five")][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn from_hash_map() {
+    use std::collections::HashMap;
+    let mut map = HashMap::new();
+    map.insert(2u8, 4u8);
+    map.insert(3, 9);
+    let mut received = from_map(&map).subscribe_collect();
+    received.sort();
+    assert_eq!(&[(2u8, 4u8), (3, 9)][..], &received[..]);
+}
+
 // Option tests
 
 #[test]
@@ -187,6 +299,213 @@ fn slice_subscribe_result() {
     assert_eq!(&received[..], &expected[..]);
 }
 
+#[test]
+fn slice_subscribe_collect() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let received = values.subscribe_collect();
+    assert_eq!(&[&2u8, &3, &5, &7, &11, &13][..], &received[..]);
+
+    let mut empty: Option<u8> = None;
+    assert!(empty.subscribe_collect().is_empty());
+}
+
+#[test]
+fn slice_collect_iter() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let sum: u8 = values.collect_iter().sum();
+    assert_eq!(41, sum);
+}
+
+#[test]
+fn slice_run_count_next() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    assert_eq!(6, values.run_count_next(|&x| received.push(x)));
+    assert_eq!(&values[..], &received[..]);
+}
+
+#[test]
+fn subscribe_vec_observer() {
+    let mut values = from_vec(vec![2u8, 3, 5, 7, 11, 13]);
+    let mut received: Vec<u8> = Vec::new();
+    values.subscribe(&mut received);
+    assert_eq!(&[2u8, 3, 5, 7, 11, 13][..], &received[..]);
+}
+
+#[test]
+fn slice_subscribe_sender() {
+    use std::sync::mpsc::channel;
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let (sender, receiver) = channel();
+    values.subscribe_sender(sender);
+
+    // The observable completed, so the sender has been dropped, and the
+    // iterator ends after the values.
+    let received: Vec<&u8> = receiver.iter().collect();
+    assert_eq!(&[&2u8, &3, &5, &7, &11, &13][..], &received[..]);
+}
+
+#[test]
+fn subscribe_sender_after_receiver_dropped() {
+    use std::sync::mpsc::channel;
+    let mut values = from_vec(vec![2u8, 3, 5]);
+    let (sender, receiver) = channel();
+    drop(receiver);
+
+    // Sending fails, but that should not panic.
+    values.subscribe_sender(sender);
+}
+
+#[test]
+fn slice_subscribe_ref() {
+    let values: &[u8] = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.subscribe_ref_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(values, &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn option_subscribe_ref() {
+    let some = Some(7u32);
+    let shared: &Option<u32> = &some;
+    let mut received = Vec::new();
+    shared.subscribe_ref_next(|x| received.push(x));
+    shared.subscribe_ref_next(|x| received.push(x));
+    assert_eq!(&[7u32, 7][..], &received[..]);
+}
+
+// Vec tests
+
+#[test]
+fn from_vec_subscribe_completed() {
+    let mut values = from_vec(vec![2u8, 3, 5, 7, 11, 13]);
+    let expected = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+
+    // Subscribing should not consume the values, so it can be done again.
+    received.clear();
+    values.subscribe_next(|x| received.push(x));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+#[allow(clippy::useless_vec)] // The test is about vectors, so it needs a vector.
+fn slice_methods_on_vectors_with_observable_in_scope() {
+    // Vectors do not implement `Observable`, so the slice methods resolve
+    // without ambiguity.
+    let values = vec![2u8, 3, 5];
+    assert_eq!(Some(&2), values.first());
+    assert_eq!(Some(&5), values.last());
+    assert!(values.contains(&3));
+}
+
+#[test]
+fn range_subscribe_completed() {
+    let mut range = rx::range(2u8, 6);
+    let mut received = Vec::new();
+    let mut completed = false;
+    range.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[2u8, 3, 4, 5][..], &received[..]);
+    assert!(completed);
+
+    // Subscribing does not consume the range, so it can be done again.
+    assert_eq!(&[2u8, 3, 4, 5][..], &range.subscribe_collect()[..]);
+}
+
+#[test]
+fn range_empty() {
+    let mut completed = false;
+    rx::range(5u8, 5).subscribe_completed(
+        |_x| panic!("empty range should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn iterator_methods_on_ranges_with_observable_in_scope() {
+    // Ranges do not implement `Observable`, so the iterator methods resolve
+    // without ambiguity.
+    assert!((0..5).any(|x| x == 3));
+    assert!((0..5).all(|x| x < 5));
+}
+
+// Observer tests
+
+#[test]
+fn map_observer_maps_values() {
+    use std::sync::mpsc::channel;
+    let (sender, receiver) = channel();
+    let mut observer = map_observer(ChannelObserver::new(sender), |x: u8| x * 2);
+    observer.on_next(2);
+    observer.on_next(3);
+    observer.on_next(5);
+    Observer::<u8, ()>::on_completed(observer);
+
+    // Completion drops the sender, so the iterator ends after the values.
+    let received: Vec<u8> = receiver.iter().collect();
+    assert_eq!(&[4u8, 6, 10][..], &received[..]);
+}
+
+#[test]
+fn subscribe_boxed_chosen_at_runtime() {
+    use std::sync::mpsc::channel;
+    for &double in &[false, true] {
+        let (sender, receiver) = channel();
+        let observer: Box<BoxedObserver<u8, ()>> = if double {
+            Box::new(map_observer(ChannelObserver::new(sender), |x: u8| x * 2))
+        } else {
+            Box::new(ChannelObserver::new(sender))
+        };
+        let mut values = from_vec(vec![2u8, 3, 5]);
+        values.subscribe_boxed(observer);
+        let received: Vec<u8> = receiver.iter().collect();
+        if double {
+            assert_eq!(&[4u8, 6, 10][..], &received[..]);
+        } else {
+            assert_eq!(&[2u8, 3, 5][..], &received[..]);
+        }
+    }
+}
+
+// Custom observable tests
+
+/// An observable that pushes a single value, with `()` as subscription.
+struct Single(u8);
+
+impl Observable for Single {
+    type Item = u8;
+    type Error = ();
+    type Subscription = ();
+
+    fn subscribe<O>(&mut self, mut observer: O) where O: Observer<u8, ()> {
+        observer.on_next(self.0);
+        observer.on_completed();
+    }
+}
+
+#[test]
+fn unit_subscription() {
+    let mut single = Single(7);
+    let mut received = Vec::new();
+    let mut completed = false;
+    single.map(|x| x * 2).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[14u8][..], &received[..]);
+    assert!(completed);
+
+    // A unit subscription composes with other subscriptions too.
+    let mut next = Single(3);
+    received.clear();
+    single.continue_with(&mut next).subscribe_next(|x| received.push(x));
+    assert_eq!(&[7u8, 3][..], &received[..]);
+}
+
 // Subject tests
 
 #[test]
@@ -238,6 +557,47 @@ fn subject_on_error() {
     assert_eq!(41, error);
 }
 
+#[test]
+fn subject_on_next_many() {
+    let mut subject = Subject::<u8, ()>::new();
+    let first = RefCell::new(Vec::new());
+    let second = RefCell::new(Vec::new());
+    let _first_subscription = subject.observable().subscribe_next(|x| first.borrow_mut().push(x));
+    let _second_subscription = subject.observable().subscribe_next(|x| second.borrow_mut().push(x));
+
+    // A batch should be delivered the same as the individual values.
+    subject.on_next(2);
+    subject.on_next_many(&[3, 5, 7]);
+    subject.on_next_many(&[]);
+    subject.on_next(11);
+    assert_eq!(&[2u8, 3, 5, 7, 11][..], &first.borrow()[..]);
+    assert_eq!(&[2u8, 3, 5, 7, 11][..], &second.borrow()[..]);
+}
+
+#[test]
+fn subject_drop_half_of_many_observers() {
+    let mut subject = Subject::<u8, ()>::new();
+    let counts: Vec<Cell<u32>> = (0..100).map(|_| Cell::new(0)).collect();
+    let mut subscriptions: Vec<_> = counts.iter().map(|count| {
+        subject.observable().subscribe_next(move |_x| count.set(count.get() + 1))
+    }).collect();
+
+    subject.on_next(2);
+
+    // Drop the subscriptions of the odd observers.
+    let mut i = 0;
+    subscriptions.retain(|_| { i += 1; i % 2 == 1 });
+
+    // The first push after dropping prunes the list, the second one pushes
+    // to the pruned list. Every survivor should receive both exactly once.
+    subject.on_next(3);
+    subject.on_next(5);
+    for (i, count) in counts.iter().enumerate() {
+        let expected = if i % 2 == 0 { 3 } else { 1 };
+        assert_eq!(expected, count.get());
+    }
+}
+
 /// Helper for the `subject_clones_once_per_observer()` test.
 struct CloneCounter {
     counter: Rc<RefCell<u32>>,
@@ -319,13 +679,165 @@ fn subject_drop_subscription_multi() {
     assert_eq!(&[2u8, 3, 5], &received[..]);
 }
 
+#[test]
+fn subject_complete_by_reference() {
+    fn finish(subject: &mut Subject<u8, ()>) {
+        subject.on_next(2);
+        subject.complete();
+    }
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    let _subscription = subject.observable().subscribe_completed(|x| received.push(x), || completed = true);
+    finish(&mut subject);
+    assert_eq!(&[2u8][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn subject_terminate_by_reference() {
+    fn terminate(completing: &mut Subject<u8, u8>, failing: &mut Subject<u8, u8>) {
+        completing.on_completed();
+        failing.on_error(17);
+    }
+    let mut completing = Subject::<u8, u8>::new();
+    let mut failing = Subject::<u8, u8>::new();
+    let mut completed = false;
+    let mut error = None;
+    let _first = completing.observable().subscribe_completed(|_| (), || completed = true);
+    let _second = failing.observable().subscribe_error(|_| (), || (), |err| error = Some(err));
+    terminate(&mut completing, &mut failing);
+    assert!(completed);
+    assert_eq!(Some(17), error);
+}
+
+#[test]
+fn subject_ignores_values_after_termination() {
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let _subscription = subject.observable().subscribe_next(|x| received.push(x));
+    subject.on_next(2);
+    subject.complete();
+    subject.on_next(3);
+    subject.fail(());
+    assert_eq!(&[2u8][..], &received[..]);
+}
+
+#[test]
+fn subject_terminates_late_subscribers() {
+    let mut completing = Subject::<u8, u8>::new();
+    let mut failing = Subject::<u8, u8>::new();
+    completing.complete();
+    failing.fail(17);
+
+    let mut completed = false;
+    completing.observable().subscribe_completed(|_| panic!("no values after completion"),
+                                                || completed = true);
+    assert!(completed);
+
+    let mut error = None;
+    failing.handle().subscribe_error(|_| panic!("no values after failure"),
+                                     || panic!("failed subject should not complete"),
+                                     |err| error = Some(err));
+    assert_eq!(Some(17), error);
+    assert_eq!(0, failing.subscriber_count());
+}
+
+#[test]
+fn pipe_into_subject() {
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    let _subscription = subject.observable().subscribe_completed(|x| received.push(x), || completed = true);
+    let mut values = from_vec(vec![2u8, 3, 5]);
+    values.pipe_into(&mut subject);
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn subject_contramap() {
+    let mut subject = Subject::<u32, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    let _subscription = subject.observable().subscribe_completed(|x| received.push(x), || completed = true);
+    let mut values = from_vec(vec![2u32, 3, 5]);
+    values.subscribe(subject.contramap(|x: u32| x * 2));
+    assert_eq!(&[4u32, 6, 10][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn subject_from_observers() {
+    let first = SharedObserver { notifications: Rc::new(RefCell::new(Vec::new())) };
+    let second = SharedObserver { notifications: Rc::new(RefCell::new(Vec::new())) };
+    let observers: Vec<Box<BoxedObserver<u8, ()>>> = vec![Box::new(first.clone()), Box::new(second.clone())];
+    let mut subject = Subject::from_observers(observers);
+    assert_eq!(2, subject.subscriber_count());
+    subject.on_next(2);
+    subject.on_next(3);
+    subject.complete();
+    let expected = vec![Notification::Next(2u8), Notification::Next(3), Notification::Completed];
+    assert_eq!(expected, *first.notifications.borrow());
+    assert_eq!(expected, *second.notifications.borrow());
+}
+
+#[test]
+fn subject_unsubscribe() {
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let subscription = subject.observable().subscribe_next(|x| received.push(x));
+    subject.on_next(2);
+    subscription.unsubscribe();
+    subject.on_next(3);
+    assert_eq!(&[2u8][..], &received[..]);
+}
+
+#[test]
+fn subscription_unsubscribe_boxed() {
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut subscriptions: Vec<Box<Subscription>> = Vec::new();
+    {
+        let mut observable = subject.observable();
+        subscriptions.push(Box::new(observable.subscribe_next(|x| received.push(x))));
+        subscriptions.push(Box::new(observable.map(|x| x * 10).subscribe_next(|x| received.push(x))));
+    }
+    subscriptions.push(Box::new(Never::<u8, ()>::new().subscribe_next(|_| ())));
+    subscriptions.push(Box::new((&[1u8, 2]).subscribe_next(|_| ())));
+    subject.on_next(1);
+    for subscription in subscriptions.drain(..) {
+        subscription.unsubscribe();
+    }
+    subject.on_next(2);
+    assert_eq!(&[1u8, 10][..], &received[..]);
+}
+
+#[test]
+fn subject_subscriber_count() {
+    let mut subject = Subject::<u8, ()>::new();
+    assert_eq!(0, subject.subscriber_count());
+    let first = subject.observable().subscribe_next(|_| ());
+    let second = subject.observable().subscribe_next(|_| ());
+    let third = subject.observable().subscribe_next(|_| ());
+    assert_eq!(3, subject.subscriber_count());
+    drop(second);
+    assert_eq!(2, subject.subscriber_count());
+    subject.on_next(1);
+    assert_eq!(2, subject.subscriber_count());
+    drop(first);
+    drop(third);
+    assert_eq!(0, subject.subscriber_count());
+}
+
 // TODO: Add a better test to test internal removal of observer from the list.
 
 #[test]
 fn subject_drop_in_handler() {
+    use std::any::Any;
     let mut subject = Subject::<u8, ()>::new();
     let mut received = Vec::new();
-    let mut subscription_opt: Option<Box<Drop>> = None;
+    let mut subscription_opt: Option<Box<Any>> = None;
     let subscription = subject.observable().subscribe_next(|x| {
         received.push(x);
         assert!(subscription_opt.is_some());
@@ -383,19 +895,440 @@ fn subject_continue_with() {
     assert!(completed);
 }
 
-// TODO: Test multiple subscriptions and combinations of values and completed/error.
-// TODO: Add better tests for dropping the subject subscription.
-
-// Transform tests
-
 #[test]
-fn map() {
-    let mut values = &[2u8, 3, 5, 7, 11, 13];
-    let expected = &[4u8, 6, 10, 14, 22, 26];
+fn subject_first() {
+    use std::mem;
+    let mut subject = Subject::<u8, ()>::new();
     let mut received = Vec::new();
-    let mut mapped = values.map(|x| x * 2);
-    mapped.subscribe_next(|x| received.push(x));
-    assert_eq!(&expected[..], &received[..]);
+    let mut completed = false;
+    {
+        let mut observable = subject.observable();
+        let mut first = observable.first();
+        let subscription = first.subscribe_completed(|x| received.push(x), || completed = true);
+        // The subscription borrows the subject, forget it to keep it alive.
+        mem::forget(subscription);
+    }
+
+    subject.on_next(2);
+    assert_eq!(&[2u8][..], &received[..]);
+    assert!(completed);
+
+    // The subscription to the subject should have been dropped.
+    subject.on_next(3);
+    assert_eq!(&[2u8][..], &received[..]);
+}
+
+#[test]
+fn subject_subscribe_during_on_next() {
+    let mut subject = Subject::<u8, ()>::new();
+    let first_received = Rc::new(RefCell::new(Vec::new()));
+    let second_received = Rc::new(RefCell::new(Vec::new()));
+    let late_subscription = Rc::new(RefCell::new(None));
+    let _subscription = {
+        let first_received = first_received.clone();
+        let second_received = second_received.clone();
+        let late_subscription = late_subscription.clone();
+        let mut handle = subject.handle();
+        subject.observable().subscribe_next(move |x| {
+            first_received.borrow_mut().push(x);
+            if x == 2 {
+                // Subscribe a second observer to the same subject, while it is
+                // pushing a value to this observer.
+                let second_received = second_received.clone();
+                let subscription = handle.subscribe_next(move |x| second_received.borrow_mut().push(x));
+                *late_subscription.borrow_mut() = Some(subscription);
+            }
+            if x == 5 {
+                // Drop the subscription of the second observer before it
+                // receives this value.
+                *late_subscription.borrow_mut() = None;
+            }
+        })
+    };
+
+    // The second observer should not receive the value that was being pushed
+    // when it subscribed, only the values after it, until it is dropped.
+    subject.on_next(2);
+    subject.on_next(3);
+    subject.on_next(5);
+    subject.on_next(7);
+    assert_eq!(&[2u8, 3, 5, 7][..], &first_received.borrow()[..]);
+    assert_eq!(&[3u8][..], &second_received.borrow()[..]);
+}
+
+#[test]
+fn behavior_subject_pushes_current_value() {
+    let mut subject = BehaviorSubject::<u8, ()>::new(2);
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    let _s1 = subject.observable().subscribe_next(|x| first.push(x));
+
+    // The initial value should be pushed upon subscription.
+    assert_eq!(&[2u8][..], &first[..]);
+
+    subject.on_next(3);
+    subject.on_next(5);
+    assert_eq!(5, *subject.value());
+
+    // A late subscriber should receive only the most recent value.
+    let _s2 = subject.observable().subscribe_next(|x| second.push(x));
+
+    subject.on_next(7);
+    assert_eq!(&[2u8, 3, 5, 7][..], &first[..]);
+    assert_eq!(&[5u8, 7][..], &second[..]);
+}
+
+#[test]
+fn behavior_subject_subscribe_after_completed() {
+    let mut subject = BehaviorSubject::<u8, ()>::new(2);
+    let mut completed = false;
+    subject.complete();
+    let _subscription = subject.observable().subscribe_completed(
+        |_x| panic!("completed behavior subject should not push a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn behavior_subject_subscribe_after_error() {
+    let mut subject = BehaviorSubject::<u8, u8>::new(2);
+    let mut error = None;
+    subject.fail(41);
+    let _subscription = subject.observable().subscribe_error(
+        |_x| panic!("failed behavior subject should not push a value"),
+        || panic!("failed behavior subject should not complete"),
+        |err| error = Some(err)
+    );
+    assert_eq!(Some(41), error);
+}
+
+#[test]
+fn subject_merge() {
+    use std::mem;
+    let mut first = Subject::<u8, ()>::new();
+    let mut second = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut completions = 0;
+    {
+        let mut second_observable = second.observable();
+        let subscription = first.observable()
+            .merge(&mut second_observable)
+            .subscribe_completed(|x| received.push(x), || completions += 1);
+
+        // The subscription borrows the subjects, forget it to keep it alive.
+        mem::forget(subscription);
+    }
+
+    first.on_next(2);
+    second.on_next(3);
+    first.on_next(5);
+    second.on_next(7);
+    assert_eq!(&[2u8, 3, 5, 7][..], &received[..]);
+
+    // Completing one subject should not complete the merged observable.
+    first.on_completed();
+    assert_eq!(0, completions);
+
+    second.on_next(11);
+    assert_eq!(&[2u8, 3, 5, 7, 11][..], &received[..]);
+
+    second.on_completed();
+    assert_eq!(1, completions);
+}
+
+#[test]
+fn subject_merge_error() {
+    use std::mem;
+    let mut first = Subject::<u8, u8>::new();
+    let mut second = Subject::<u8, u8>::new();
+    let mut error = None;
+    {
+        let mut second_observable = second.observable();
+        let subscription = first.observable()
+            .merge(&mut second_observable)
+            .subscribe_error(
+                |_x| panic!("no value should be pushed"),
+                || panic!("merged observable should not complete"),
+                |err| error = Some(err)
+            );
+        mem::forget(subscription);
+    }
+
+    second.on_error(41);
+    assert_eq!(Some(41), error);
+
+    // After the error, the other source should be ignored.
+    first.on_next(2);
+    first.on_completed();
+}
+
+#[test]
+fn amb_synchronous_source_skips_other() {
+    let subscriptions = Cell::new(0);
+    let mut values = &[2u8, 3, 5];
+    let mut other = defer(|| {
+        subscriptions.set(subscriptions.get() + 1);
+        &[7u8, 11][..]
+    });
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.amb(&mut other).subscribe_completed(|x| received.push(*x), || completed = true);
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+    assert!(completed);
+
+    // The source won before `subscribe()` returned, so the other observable
+    // should not have been subscribed to.
+    assert_eq!(0, subscriptions.get());
+}
+
+#[test]
+fn subject_amb() {
+    use std::mem;
+    let mut first = Subject::<u8, ()>::new();
+    let mut second = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut second_observable = second.observable();
+        let subscription = first.observable()
+            .amb(&mut second_observable)
+            .subscribe_completed(|x| received.push(x), || completed = true);
+
+        // The subscription borrows the subjects, forget it to keep it alive.
+        mem::forget(subscription);
+    }
+
+    // The second subject pushes first, so it wins.
+    second.on_next(3);
+    first.on_next(2);
+    second.on_next(5);
+    assert_eq!(&[3u8, 5][..], &received[..]);
+
+    // Completion of the loser should be ignored too.
+    first.on_completed();
+    assert!(!completed);
+
+    second.on_completed();
+    assert!(completed);
+}
+
+#[test]
+fn subject_take_until() {
+    let mut source = Subject::<u8, ()>::new();
+    let mut stop = Subject::<(), ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut source_handle = source.handle();
+        let mut stop_handle = stop.handle();
+        let mut taken = source_handle.take_until(&mut stop_handle);
+        let _subscription = taken.subscribe_completed(|x| received.push(x), || completed = true);
+        source.on_next(2);
+        source.on_next(3);
+        assert!(!completed);
+        stop.on_next(());
+        assert!(completed);
+
+        // The subscription to the source is dropped when stop fires.
+        assert_eq!(0, source.subscriber_count());
+        source.on_next(5);
+    }
+    assert_eq!(&[2u8, 3][..], &received[..]);
+}
+
+#[test]
+fn subject_skip_until() {
+    let mut source = Subject::<u8, ()>::new();
+    let mut gate = Subject::<(), ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut source_handle = source.handle();
+        let mut gate_handle = gate.handle();
+        let mut skipped = source_handle.skip_until(&mut gate_handle);
+        let _subscription = skipped.subscribe_completed(|x| received.push(x), || completed = true);
+        source.on_next(2);
+        source.on_next(3);
+        gate.on_next(());
+        source.on_next(5);
+        gate.complete();
+        source.on_next(7);
+        assert!(!completed);
+        source.complete();
+    }
+    assert_eq!(&[5u8, 7][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn subject_finally_drop_subscription() {
+    let mut subject = Subject::<u8, ()>::new();
+    let calls = Cell::new(0);
+    let mut received = Vec::new();
+    {
+        let mut observable = subject.observable();
+        let mut finally = observable.finally(|| calls.set(calls.get() + 1));
+        let subscription = finally.subscribe_next(|x| received.push(x));
+        assert_eq!(0, calls.get());
+
+        // Dropping the subscription before the subject terminates should
+        // call the function.
+        drop(subscription);
+        assert_eq!(1, calls.get());
+    }
+
+    subject.on_next(2);
+    subject.on_completed();
+    assert_eq!(1, calls.get());
+    assert_eq!(0, received.len());
+}
+
+#[test]
+fn subject_zip() {
+    use std::mem;
+    let mut first = Subject::<u8, ()>::new();
+    let mut second = Subject::<char, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut second_observable = second.observable();
+        let subscription = first.observable()
+            .zip(&mut second_observable)
+            .subscribe_completed(|x| received.push(x), || completed = true);
+
+        // The subscription borrows the subjects, forget it to keep it alive.
+        mem::forget(subscription);
+    }
+
+    first.on_next(2);
+    first.on_next(3);
+    assert_eq!(0, received.len());
+
+    second.on_next('a');
+    assert_eq!(&[(2u8, 'a')][..], &received[..]);
+
+    // The first subject has a value buffered, so it is not done yet.
+    first.on_completed();
+    assert!(!completed);
+
+    second.on_next('b');
+    assert_eq!(&[(2u8, 'a'), (3, 'b')][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn subject_combine_latest() {
+    use std::mem;
+    let mut first = Subject::<u8, ()>::new();
+    let mut second = Subject::<char, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut second_observable = second.observable();
+        let subscription = first.observable()
+            .combine_latest(&mut second_observable)
+            .subscribe_completed(|x| received.push(x), || completed = true);
+
+        // The subscription borrows the subjects, forget it to keep it alive.
+        mem::forget(subscription);
+    }
+
+    // Nothing should be pushed before both subjects produced a value.
+    first.on_next(2);
+    first.on_next(3);
+    assert_eq!(0, received.len());
+
+    second.on_next('a');
+    assert_eq!(&[(3u8, 'a')][..], &received[..]);
+
+    first.on_next(5);
+    second.on_next('b');
+    assert_eq!(&[(3u8, 'a'), (5, 'a'), (5, 'b')][..], &received[..]);
+
+    first.on_completed();
+    assert!(!completed);
+
+    // The most recent value of a completed subject is still used.
+    second.on_next('c');
+    assert_eq!(&[(3u8, 'a'), (5, 'a'), (5, 'b'), (5, 'c')][..], &received[..]);
+
+    second.on_completed();
+    assert!(completed);
+}
+
+#[test]
+fn subject_combine_latest_with() {
+    let mut first = Subject::<u8, ()>::new();
+    let mut second = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut first_handle = first.handle();
+        let mut second_handle = second.handle();
+        let mut combined = first_handle.combine_latest_with(&mut second_handle, |a, b| a + b);
+        let _subscription = combined.subscribe_completed(|x| received.push(x), || completed = true);
+        first.on_next(2);
+        second.on_next(10);
+        first.on_next(3);
+        second.on_next(20);
+        first.complete();
+        second.complete();
+    }
+    assert_eq!(&[12u8, 13, 23][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn subject_with_latest_from() {
+    use std::mem;
+    let mut first = Subject::<u8, ()>::new();
+    let mut second = Subject::<char, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut second_observable = second.observable();
+        let subscription = first.observable()
+            .with_latest_from(&mut second_observable)
+            .subscribe_completed(|x| received.push(x), || completed = true);
+
+        // The subscription borrows the subjects, forget it to keep it alive.
+        mem::forget(subscription);
+    }
+
+    // Values of the first subject are dropped while the second has no value.
+    first.on_next(2);
+    assert_eq!(0, received.len());
+
+    // Values of the second subject alone should not be pushed.
+    second.on_next('a');
+    second.on_next('b');
+    assert_eq!(0, received.len());
+
+    first.on_next(3);
+    first.on_next(5);
+    assert_eq!(&[(3u8, 'b'), (5, 'b')][..], &received[..]);
+
+    second.on_completed();
+    assert!(!completed);
+
+    first.on_completed();
+    assert!(completed);
+}
+
+// TODO: Test multiple subscriptions and combinations of values and completed/error.
+// TODO: Add better tests for dropping the subject subscription.
+
+// Transform tests
+
+#[test]
+fn map() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[4u8, 6, 10, 14, 22, 26];
+    let mut received = Vec::new();
+    let mut mapped = values.map(|x| x * 2);
+    mapped.subscribe_next(|x| received.push(x));
+    assert_eq!(&expected[..], &received[..]);
 }
 
 #[test]
@@ -410,6 +1343,39 @@ fn map_does_not_change_error() {
     assert_eq!(Some(23), error);
 }
 
+#[test]
+fn map_to() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.map_to(()).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(6, received.len());
+    assert!(completed);
+}
+
+#[test]
+fn try_map() {
+    let mut subject = Subject::<u8, u8>::new();
+    let mut received = Vec::new();
+    let mut error = None;
+    let _subscription = {
+        let mut observable = subject.observable();
+        let mut mapped = observable.try_map(|x| if x == 5 { Err(x) } else { Ok(x * 2) });
+        mapped.subscribe_error(
+            |x| received.push(x),
+            || panic!("try_map should not complete after an error"),
+            |err| error = Some(err)
+        )
+    };
+    subject.on_next(2);
+    subject.on_next(3);
+    subject.on_next(5);
+    subject.on_next(7);
+    assert_eq!(&[4u8, 6][..], &received[..]);
+    assert_eq!(Some(5), error);
+    assert_eq!(0, subject.subscriber_count());
+}
+
 #[test]
 fn map_error() {
     let mut error = None;
@@ -441,3 +1407,1676 @@ fn continue_with() {
     continued.subscribe_next(|&x| received.push(x));
     assert_eq!(&expected[..], &received[..]);
 }
+
+#[test]
+fn chain() {
+    let mut first = Some(2u8);
+    let mut received = Vec::new();
+    let mut completed = false;
+    first.chain(Some(3)).chain(Some(5)).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn enumerate() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[(0, 2u8), (1, 3), (2, 5), (3, 7), (4, 11), (5, 13)];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut enumerated = values.enumerate();
+    enumerated.subscribe_completed(|(i, &x)| received.push((i, x)), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn index_interval() {
+    let mut values = &[2u8, 3, 5];
+    let expected = &[(2u8, 0), (3, 1), (5, 1)];
+    let mut received = Vec::new();
+    values.index_interval().subscribe_next(|(&x, d)| received.push((x, d)));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+fn index_interval_by() {
+    // Sequence numbers with gaps, the distance should reveal the gaps.
+    let mut values = &[1u8, 2, 5, 6, 10];
+    let expected = &[(1u8, 0), (2, 1), (5, 3), (6, 1), (10, 4)];
+    let mut received = Vec::new();
+    values.index_interval_by(|_i, &&x| x as usize).subscribe_next(|(&x, d)| received.push((x, d)));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+fn filter() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[7u8, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut filtered = values.filter(|&&x| x > 5);
+    filtered.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn filter_map() {
+    let mut values = &[2u8, 3, 4, 7, 10, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut halves = values.filter_map(|&x| if x % 2 == 0 { Some(x / 2) } else { None });
+    halves.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[1u8, 2, 5][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn skip() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[7u8, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut skipped = values.skip(3);
+    skipped.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn skip_more_than_available() {
+    let mut values = &[2u8, 3, 5];
+    let mut completed = false;
+    let mut skipped = values.skip(5);
+    skipped.subscribe_completed(
+        |_x| panic!("no value should be pushed"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn skip_last() {
+    let mut values = rx::range(0u32, 5);
+    let expected = &[0u32, 1, 2];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut skipped = values.skip_last(2);
+    skipped.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn delay_by_count() {
+    let mut subject = Subject::<u32, ()>::new();
+    let received = RefCell::new(Vec::new());
+    let completed = Cell::new(false);
+    let mut values = rx::range(0u32, 5);
+    {
+        let _subscription = subject.observable().delay_by_count(2).subscribe_completed(
+            |x| received.borrow_mut().push(x),
+            || completed.set(true)
+        );
+
+        // Every value is pushed when the value two places later arrives.
+        subject.on_next(0);
+        subject.on_next(1);
+        assert!(received.borrow().is_empty());
+        subject.on_next(2);
+        assert_eq!(&[0u32][..], &received.borrow()[..]);
+        subject.on_next(3);
+        assert_eq!(&[0u32, 1][..], &received.borrow()[..]);
+
+        // On completion, the delayed values are flushed.
+        subject.on_completed();
+        assert_eq!(&[0u32, 1, 2, 3][..], &received.borrow()[..]);
+        assert!(completed.get());
+    }
+
+    received.borrow_mut().clear();
+    values.delay_by_count(2).subscribe_next(|x| received.borrow_mut().push(x));
+    assert_eq!(&[0u32, 1, 2, 3, 4][..], &received.borrow()[..]);
+}
+
+#[test]
+fn sample_every() {
+    let mut values = rx::range(0u32, 10);
+    let expected = &[0u32, 3, 6, 9];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut sampled = values.sample_every(3);
+    sampled.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn sample_first_of() {
+    let mut values = rx::range(0u32, 9);
+    let mut received = Vec::new();
+    values.sample_first_of(3).subscribe_next(|x| received.push(x));
+    assert_eq!(&[0u32, 3, 6][..], &received[..]);
+}
+
+#[test]
+fn distinct() {
+    let mut values = &[3u8, 2, 3, 5, 2, 7, 5, 3];
+    let expected = &[3u8, 2, 5, 7];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut distinct = values.distinct();
+    distinct.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn distinct_by() {
+    let mut values = &[2.0f32, 3.5, -2.0, 5.0, 3.5, -5.0];
+    let expected = &[2.0f32, 3.5, 5.0];
+    let mut received = Vec::new();
+    let mut distinct = values.distinct_by(|&&x| x.abs() as u8);
+    distinct.subscribe_next(|&x| received.push(x));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+fn scan() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[2u8, 5, 10, 17, 28, 41];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut scanned = values.scan(0, |acc, &x| acc + x);
+    scanned.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn scan_unseeded_sum() {
+    let mut values = &[1u8, 2, 3];
+    let mut received = Vec::new();
+    values.scan(0, |acc, &x| acc + x).subscribe_next(|x| received.push(x));
+    assert_eq!(&[1u8, 3, 6][..], &received[..]);
+}
+
+#[test]
+fn scan_seeded_sum() {
+    let mut values = &[1u8, 2, 3];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut scanned = values.scan_seeded(0, |acc, &x| acc + x);
+    scanned.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[0u8, 1, 3, 6][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn fold() {
+    let mut values = &[2u32, 3, 5, 7, 11, 13];
+    let expected = &[Some(30030u32), None];
+    let mut received = Vec::new();
+    let mut folded = values.fold(1, |acc, &x| acc * x);
+    folded.subscribe_option(|x| received.push(x));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+fn fold_does_not_push_on_error() {
+    let mut error = None;
+    let mut observable = Err(23_u32);
+    let mut folded = observable.fold(0, |acc: u32, x: u32| acc + x);
+    folded.subscribe_error(
+        |_x| panic!("folded error should not produce a value"),
+        || panic!("folded error should not complete"),
+        |err| error = Some(err)
+    );
+    assert_eq!(Some(23), error);
+}
+
+#[test]
+fn last() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[Some(13u8), None];
+    let mut received = Vec::new();
+    let mut last = values.last();
+    last.subscribe_option(|x| received.push(x.cloned()));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+fn last_of_empty() {
+    let mut completed = false;
+    let mut observable: Option<u8> = None;
+    let mut last = observable.last();
+    last.subscribe_completed(
+        |_x| panic!("last of empty observable should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn single() {
+    let mut values = &[2u8];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.single().subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&[2u8][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn single_of_empty() {
+    let mut empty: Option<u8> = None;
+    let mut failed = false;
+    empty.single().subscribe_error(
+        |_x| panic!("single of empty observable should not produce a value"),
+        || panic!("single of empty observable should not complete"),
+        |()| failed = true
+    );
+    assert!(failed);
+
+    // Errors of the observable are passed through.
+    let mut observable: Result<u8, SingleError> = Err(SingleError::Empty);
+    let mut error = None;
+    observable.single().subscribe_error(|_x| (), || (), |err| error = Some(err));
+    assert_eq!(Some(SingleError::Empty), error);
+}
+
+#[test]
+fn single_of_multiple() {
+    let mut subject = Subject::<u8, SingleError>::new();
+    let mut error = None;
+    {
+        let mut handle = subject.handle();
+        let mut single = handle.single();
+        let _subscription = single.subscribe_error(
+            |_x| panic!("single of multiple values should not produce a value"),
+            || panic!("single of multiple values should not complete"),
+            |err| error = Some(err)
+        );
+        subject.on_next(2);
+        assert_eq!(1, subject.subscriber_count());
+
+        // The second value fails immediately, and drops the subscription.
+        subject.on_next(3);
+        assert_eq!(0, subject.subscriber_count());
+    }
+    assert_eq!(Some(SingleError::Multiple), error);
+}
+
+#[test]
+fn first() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[Some(2u8), None];
+    let mut received = Vec::new();
+    let mut first = values.first();
+    first.subscribe_option(|x| received.push(x.cloned()));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+fn first_of_empty() {
+    let mut completed = false;
+    let mut observable: Option<u8> = None;
+    let mut first = observable.first();
+    first.subscribe_completed(
+        |_x| panic!("first of empty observable should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn element_at() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[Some(5u8), None];
+    let mut received = Vec::new();
+    let mut third = values.element_at(2);
+    third.subscribe_option(|x| received.push(x.cloned()));
+    assert_eq!(&expected[..], &received[..]);
+}
+
+#[test]
+fn element_at_out_of_range() {
+    let mut values = &[2u8, 3, 5];
+    let mut completed = false;
+    let mut fourth = values.element_at(3);
+    fourth.subscribe_completed(
+        |_x| panic!("element_at past the end should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn element_at_subject() {
+    use std::mem;
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let subscription = subject.observable()
+            .element_at(1)
+            .subscribe_completed(|x| received.push(x), || completed = true);
+        mem::forget(subscription);
+    }
+    subject.on_next(2);
+    subject.on_next(3);
+    subject.on_next(5);
+    assert_eq!(&[3u8][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn merge() {
+    let (mut first, mut second) = (&[2u8, 3, 5, 7], &[11u8, 13, 17, 19]);
+    let expected = &[2u8, 3, 5, 7, 11, 13, 17, 19];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut merged = first.merge(&mut second);
+    merged.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn concat_options() {
+    let mut sources = [Some(2u8), None, Some(3), Some(5)];
+    let expected = &[2u8, 3, 5];
+    let mut received = Vec::new();
+    let mut completed = false;
+    concat(&mut sources).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn concat_slices() {
+    let mut sources = [&[2u8, 3], &[5, 7], &[11, 13]];
+    let expected = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    concat(&mut sources).subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn concat_empty() {
+    let mut sources: [Option<u8>; 0] = [];
+    let mut completed = false;
+    concat(&mut sources).subscribe_completed(
+        |_x| panic!("empty concatenation should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn concat_error() {
+    let mut sources = [Ok(2u8), Err(17u8), Ok(3)];
+    let mut received = Vec::new();
+    let mut error = None;
+    concat(&mut sources).subscribe_error(
+        |x| received.push(x),
+        || panic!("concatenation with error should not complete"),
+        |err| error = Some(err)
+    );
+    assert_eq!(&[2u8][..], &received[..]);
+    assert_eq!(Some(17), error);
+}
+
+#[test]
+fn merge_all_subjects() {
+    let mut subjects = vec![Subject::<u8, ()>::new(), Subject::new(), Subject::new()];
+    let mut sources: Vec<_> = subjects.iter().map(|s| s.handle()).collect();
+    let received = RefCell::new(Vec::new());
+    let completed = Cell::new(false);
+    let _subscription = merge_all(&mut sources).subscribe_completed(
+        |x| received.borrow_mut().push(x),
+        || completed.set(true)
+    );
+    subjects[1].on_next(3);
+    subjects[0].on_next(2);
+    subjects[2].on_next(5);
+    let third = subjects.pop().unwrap();
+    let first = subjects.remove(0);
+    first.on_completed();
+    subjects[0].on_next(7);
+    third.on_completed();
+    assert!(!completed.get());
+    subjects.pop().unwrap().on_completed();
+    assert_eq!(&[3u8, 2, 5, 7][..], &received.borrow()[..]);
+    assert!(completed.get());
+}
+
+#[test]
+fn merge_all_empty() {
+    let mut sources: [Option<u8>; 0] = [];
+    let mut completed = false;
+    merge_all(&mut sources).subscribe_completed(
+        |_x| panic!("empty merge should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn merge_all_error() {
+    let mut subjects = vec![Subject::<u8, u8>::new(), Subject::new()];
+    let mut sources: Vec<_> = subjects.iter().map(|s| s.handle()).collect();
+    let mut received = Vec::new();
+    let mut error = None;
+    let _subscription = merge_all(&mut sources).subscribe_error(
+        |x| received.push(x),
+        || panic!("merge with error should not complete"),
+        |err| error = Some(err)
+    );
+    subjects[0].on_next(2);
+    subjects.remove(1).on_error(17);
+    subjects[0].on_next(3);
+    subjects.pop().unwrap().on_completed();
+    assert_eq!(&[2u8][..], &received[..]);
+    assert_eq!(Some(17), error);
+}
+
+#[test]
+fn merge_all_delay_error() {
+    let mut subjects = [Subject::<u8, u8>::new(), Subject::new(), Subject::new()];
+    let mut sources: Vec<_> = subjects.iter().map(|s| s.handle()).collect();
+    let mut received = Vec::new();
+    let mut error = None;
+    let _subscription = rx::merge_all_delay_error(&mut sources).subscribe_error(
+        |x| received.push(x),
+        || panic!("merge with error should not complete"),
+        |err| error = Some(err)
+    );
+    subjects[0].on_next(2);
+    subjects[1].fail(17);
+
+    // The other sources keep pushing values after the error.
+    subjects[0].on_next(3);
+    subjects[2].on_next(5);
+    subjects[2].fail(19);
+    subjects[0].on_next(7);
+    assert_eq!(None, error);
+    subjects[0].complete();
+    assert_eq!(&[2u8, 3, 5, 7][..], &received[..]);
+
+    // Only the first error is passed on.
+    assert_eq!(Some(17), error);
+}
+
+#[test]
+fn merge_all_delay_error_during_subscribe() {
+    let mut sources = [Err(17), Ok(2u8), Err(19), Ok(3)];
+    let mut received = Vec::new();
+    let mut error = None;
+
+    // The first source fails during subscription, but the later sources are
+    // still subscribed to.
+    rx::merge_all_delay_error(&mut sources).subscribe_error(
+        |x| received.push(x),
+        || panic!("merge with error should not complete"),
+        |err| error = Some(err)
+    );
+    assert_eq!(&[2u8, 3][..], &received[..]);
+    assert_eq!(Some(17), error);
+}
+
+#[test]
+fn start_with() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let expected = &[0u8, 2, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut started = values.start_with(vec![&0]);
+    started.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn start_with_observable() {
+    let mut values = from_vec(vec![2u8, 3, 5, 7, 11, 13]);
+    let mut head = Some(0u8);
+    let expected = &[0u8, 2, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut started = values.start_with_observable(&mut head);
+    started.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn start_with_nothing() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut started = values.start_with(Vec::new());
+    started.subscribe_next(|&x| received.push(x));
+    assert_eq!(&values[..], &received[..]);
+}
+
+#[test]
+fn catch_error() {
+    let mut observable: Result<u8, &str> = Err("boom");
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut caught = observable.catch_error(|err| {
+        assert_eq!("boom", err);
+        Ok(42)
+    });
+    caught.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[42u8][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn catch_error_does_not_change_values() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut caught = values.catch_error(|_err| None);
+    caught.subscribe_next(|&x| received.push(x));
+    assert_eq!(&values[..], &received[..]);
+}
+
+#[test]
+fn on_error_return() {
+    let mut observable: Result<u8, ()> = Err(());
+    let mut received = Vec::new();
+    let mut completed = false;
+    observable.on_error_return(42).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[42u8][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn on_error_return_after_values() {
+    let mut subject = Subject::<u8, &str>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    let _subscription = {
+        let mut observable = subject.observable();
+        let mut recovered = observable.on_error_return_with(|err| err.len() as u8);
+        recovered.subscribe_completed(|x| received.push(x), || completed = true)
+    };
+    subject.on_next(2);
+    subject.on_next(3);
+    subject.on_error("boom");
+    assert_eq!(&[2u8, 3, 4][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn on_error_complete() {
+    let mut observable: Result<u8, &str> = Err("boom");
+    let mut completed = false;
+    observable.on_error_complete().subscribe_error(
+        |_x| panic!("failed observable should not produce a value"),
+        || completed = true,
+        |_err| panic!("error should have been turned into completion")
+    );
+    assert!(completed);
+}
+
+#[test]
+fn on_error_complete_does_not_change_values() {
+    let mut values = &[2u8, 3, 5];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.on_error_complete().subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&values[..], &received[..]);
+    assert!(completed);
+}
+
+/// Helper for the `retry()` tests, an observable that fails a number of times.
+struct Flaky {
+    failures_left: u32,
+}
+
+impl Observable for Flaky {
+    type Item = u8;
+    type Error = u32;
+    type Subscription = rx::UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, mut observer: O) -> Self::Subscription
+        where O: Observer<u8, u32> {
+        observer.on_next(2);
+        if self.failures_left > 0 {
+            self.failures_left -= 1;
+            observer.on_error(self.failures_left);
+        } else {
+            observer.on_next(3);
+            observer.on_completed();
+        }
+        rx::UncancellableSubscription
+    }
+}
+
+#[test]
+fn repeat_sequence() {
+    let mut values = &[2u8, 3, 5];
+    let expected = &[2u8, 3, 5, 2, 3, 5, 2, 3, 5];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut repeated = values.repeat(3);
+    repeated.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn repeat_sequence_zero_times() {
+    let mut values = &[2u8, 3, 5];
+    let mut completed = false;
+    let mut repeated = values.repeat(0);
+    repeated.subscribe_completed(
+        |_x| panic!("repeating zero times should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn repeat_when() {
+    let mut values = &[2u8, 3];
+    let mut notifier = Subject::<(), ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut notifier_handle = notifier.handle();
+        let mut repeated = values.repeat_when(&mut notifier_handle);
+        let _subscription = repeated.subscribe_completed(|&x| received.push(x), || completed = true);
+        notifier.on_next(());
+        notifier.on_next(());
+        notifier.on_completed();
+    }
+    assert_eq!(&[2u8, 3, 2, 3, 2, 3][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn repeat_when_after_operator() {
+    let mut values = &[2u8, 3];
+    let mut notifier = Subject::<(), ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut notifier_handle = notifier.handle();
+        let mut mapped = values.map(|&x| x * 10);
+        let mut repeated = mapped.repeat_when(&mut notifier_handle);
+        let _subscription = repeated.subscribe_completed(|x| received.push(x), || completed = true);
+        notifier.on_next(());
+        notifier.complete();
+    }
+    assert_eq!(&[20u8, 30, 20, 30][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn retry() {
+    let mut flaky = Flaky { failures_left: 2 };
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut retried = flaky.retry(2);
+    retried.subscribe_completed(|x| received.push(x), || completed = true);
+
+    // Values of the failed attempts are pushed too.
+    assert_eq!(&[2u8, 2, 2, 3][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn retry_exhausted() {
+    let mut flaky = Flaky { failures_left: 3 };
+    let mut received = Vec::new();
+    let mut error = None;
+    let mut retried = flaky.retry(2);
+    retried.subscribe_error(
+        |x| received.push(x),
+        || panic!("exhausted retry should not complete"),
+        |err| error = Some(err)
+    );
+    assert_eq!(&[2u8, 2, 2][..], &received[..]);
+    assert_eq!(Some(0), error);
+}
+
+/// An observable that subscribes to the next subject every time.
+struct SubjectSequence {
+    handles: Vec<SubjectHandle<u8, u32>>,
+    next: usize,
+}
+
+impl SubjectSequence {
+    fn new(subjects: &[Subject<u8, u32>]) -> SubjectSequence {
+        SubjectSequence {
+            handles: subjects.iter().map(|subject| subject.handle()).collect(),
+            next: 0,
+        }
+    }
+}
+
+impl Observable for SubjectSequence {
+    type Item = u8;
+    type Error = u32;
+    type Subscription = Box<Subscription>;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<u8, u32> {
+        self.next += 1;
+        Box::new(self.handles[self.next - 1].subscribe(observer))
+    }
+}
+
+#[test]
+fn repeat_sequence_subject() {
+    let mut subjects = [Subject::new(), Subject::new(), Subject::new()];
+    let mut source = SubjectSequence::new(&subjects);
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut repeated = source.repeat(3);
+        let _subscription = repeated.subscribe_completed(|x| received.push(x), || completed = true);
+
+        // The rounds complete after the call to `subscribe()` returned, and
+        // every completion subscribes to the next subject.
+        for (i, subject) in subjects.iter_mut().enumerate() {
+            assert_eq!(1, subject.subscriber_count());
+            subject.on_next(i as u8);
+            subject.complete();
+        }
+    }
+    assert_eq!(&[0u8, 1, 2][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn retry_subject() {
+    let mut subjects = [Subject::new(), Subject::new(), Subject::new()];
+    let mut source = SubjectSequence::new(&subjects);
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut retried = source.retry(2);
+        let _subscription = retried.subscribe_completed(|x| received.push(x), || completed = true);
+        subjects[0].on_next(2);
+
+        // The attempts fail after the call to `subscribe()` returned, and every
+        // failure subscribes to the next subject.
+        subjects[0].fail(0);
+        assert_eq!(1, subjects[1].subscriber_count());
+        subjects[1].fail(1);
+        assert_eq!(1, subjects[2].subscriber_count());
+        subjects[2].on_next(3);
+        subjects[2].on_next(5);
+        subjects[2].complete();
+    }
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn retry_when() {
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut handled = 0;
+    {
+        let mut handle = subject.handle();
+        let mut retried = handle.retry_when(|()| {
+            handled += 1;
+            if handled == 1 { Some(()) } else { None }
+        });
+        let _subscription = retried.subscribe_completed(|x| received.push(x), || completed = true);
+        subject.on_next(2);
+
+        // The failed subject fails again upon subscription, so the handler is
+        // called twice, and the second notifier completes without a value.
+        subject.on_error(());
+    }
+    assert_eq!(&[2u8][..], &received[..]);
+    assert_eq!(2, handled);
+    assert!(completed);
+}
+
+#[test]
+fn retry_when_synchronous() {
+    let mut flaky = Flaky { failures_left: 2 };
+    let mut received = Vec::new();
+    let mut completed = false;
+
+    // The attempts fail during subscription, and the notifiers fire
+    // immediately, so the retries happen before `subscribe()` returns.
+    let mut retried = flaky.retry_when(|_err| Ok::<(), u32>(()));
+    retried.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[2u8, 2, 2, 3][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn retry_when_after_operator() {
+    let mut flaky = Flaky { failures_left: 1 };
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut mapped = flaky.map(|x| x * 10);
+    let mut retried = mapped.retry_when(|_err| Ok::<(), u32>(()));
+    retried.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[20u8, 20, 30][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn inspect() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let inspected = RefCell::new(Vec::new());
+    let mut received = Vec::new();
+    let mut observable = values.inspect(|&&x| inspected.borrow_mut().push(x));
+    observable.subscribe_next(|&x| received.push(x));
+    assert_eq!(&values[..], &inspected.borrow()[..]);
+    assert_eq!(&values[..], &received[..]);
+}
+
+#[test]
+fn inspect_completed() {
+    let mut values = &[2u8, 3, 5];
+    let inspected = RefCell::new(false);
+    let mut completed = false;
+    let mut observable = values.inspect_completed(|| *inspected.borrow_mut() = true);
+    observable.subscribe_completed(|_x| assert!(!*inspected.borrow()), || completed = true);
+    assert!(*inspected.borrow());
+    assert!(completed);
+}
+
+#[test]
+fn inspect_error() {
+    let mut observable: Result<u8, u8> = Err(17);
+    let inspected = RefCell::new(None);
+    let mut error = None;
+    let mut inspecting = observable.inspect_error(|&err| *inspected.borrow_mut() = Some(err));
+    inspecting.subscribe_error(
+        |_x| panic!("err result should not push a value"),
+        || panic!("err result should not complete"),
+        |err| error = Some(err)
+    );
+    assert_eq!(Some(17), *inspected.borrow());
+    assert_eq!(Some(17), error);
+}
+
+#[test]
+fn checked() {
+    let mut values = &[2u8, 3, 5];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.checked().subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+    assert!(completed);
+}
+
+/// An observable that violates the contract by pushing after completion.
+struct PushAfterCompleted;
+
+impl Observable for PushAfterCompleted {
+    type Item = u8;
+    type Error = ();
+    type Subscription = rx::UncancellableSubscription;
+
+    fn subscribe<O>(&mut self, observer: O) -> Self::Subscription
+        where O: Observer<u8, ()> {
+        use std::mem::ManuallyDrop;
+        use std::ptr;
+        // Keep a duplicate of the observer that is never dropped, so the
+        // observer is dropped only once, even when the call below panics.
+        let mut duplicate = ManuallyDrop::new(unsafe { ptr::read(&observer) });
+        observer.on_completed();
+        duplicate.on_next(2);
+        rx::UncancellableSubscription
+    }
+}
+
+#[test]
+#[should_panic(expected = "on_next called after termination")]
+fn checked_on_next_after_completed() {
+    PushAfterCompleted.checked().subscribe_next(|_x| ());
+}
+
+#[test]
+fn instrument_subscribe() {
+    let mut values = &[2u8, 3, 5];
+    let mut subscriptions = 0;
+    let mut received = Vec::new();
+    {
+        let mut instrumented = values.instrument_subscribe(|| subscriptions += 1);
+        instrumented.subscribe_next(|&x| received.push(x));
+        instrumented.subscribe_next(|&x| received.push(x));
+    }
+    assert_eq!(2, subscriptions);
+    assert_eq!(&[2u8, 3, 5, 2, 3, 5][..], &received[..]);
+}
+
+/// Helper for the `tap()` test, an observer that can be cloned.
+#[derive(Clone)]
+struct SharedObserver {
+    notifications: Rc<RefCell<Vec<Notification<u8, ()>>>>,
+}
+
+impl Observer<u8, ()> for SharedObserver {
+    fn on_next(&mut self, item: u8) {
+        self.notifications.borrow_mut().push(Notification::Next(item));
+    }
+
+    fn on_completed(self) {
+        self.notifications.borrow_mut().push(Notification::Completed);
+    }
+
+    fn on_error(self, error: ()) {
+        self.notifications.borrow_mut().push(Notification::Error(error));
+    }
+}
+
+#[test]
+fn tap() {
+    let mut values = from_vec(vec![2u8, 3, 5]);
+    let side = SharedObserver { notifications: Rc::new(RefCell::new(Vec::new())) };
+    let mut received = Vec::new();
+    values.tap(side.clone()).materialize().subscribe_next(|x| received.push(x));
+    let expected = vec![
+        Notification::Next(2u8),
+        Notification::Next(3),
+        Notification::Next(5),
+        Notification::Completed,
+    ];
+    assert_eq!(expected, received);
+    assert_eq!(expected, *side.notifications.borrow());
+}
+
+#[test]
+fn finally_completed() {
+    let mut values = &[2u8, 3, 5];
+    let calls = Cell::new(0);
+    let mut completed = false;
+    {
+        let mut finally = values.finally(|| calls.set(calls.get() + 1));
+        let _subscription = finally.subscribe_completed(|_x| (), || completed = true);
+
+        // The function should be called upon completion, and not again when
+        // the subscription is dropped.
+        assert_eq!(1, calls.get());
+    }
+    assert_eq!(1, calls.get());
+    assert!(completed);
+}
+
+#[test]
+fn finally_error() {
+    let mut observable: Result<u8, u8> = Err(17);
+    let calls = Cell::new(0);
+    let mut finally = observable.finally(|| calls.set(calls.get() + 1));
+    finally.subscribe_error(
+        |_x| panic!("err result should not push a value"),
+        || panic!("err result should not complete"),
+        |_err| ()
+    );
+    assert_eq!(1, calls.get());
+}
+
+/// Helper for the `on_unsubscribe()` tests, a subscription that logs its teardown.
+struct LoggingSubscription<'a> {
+    log: &'a RefCell<Vec<&'static str>>,
+}
+
+impl<'a> Drop for LoggingSubscription<'a> {
+    fn drop(&mut self) {
+        self.log.borrow_mut().push("subscription dropped");
+    }
+}
+
+#[test]
+fn on_unsubscribe_wrapper_order() {
+    let log = RefCell::new(Vec::new());
+    let subscription = LoggingSubscription { log: &log };
+    let wrapper = OnUnsubscribe::new(subscription, || log.borrow_mut().push("closure called"));
+    assert!(log.borrow().is_empty());
+    drop(wrapper);
+    assert_eq!(&["subscription dropped", "closure called"][..], &log.borrow()[..]);
+}
+
+#[test]
+fn on_unsubscribe() {
+    let subject = Subject::<u8, ()>::new();
+    let calls = Cell::new(0);
+    let count_on_unsubscribe = Cell::new(None);
+    {
+        let mut handle = subject.handle();
+        let subject_ref = &subject;
+        let mut observable = handle.on_unsubscribe(|| {
+            calls.set(calls.get() + 1);
+            count_on_unsubscribe.set(Some(subject_ref.subscriber_count()));
+        });
+        let subscription = observable.subscribe_next(|_x| ());
+        assert_eq!(1, subject.subscriber_count());
+        assert_eq!(0, calls.get());
+        drop(subscription);
+    }
+
+    // The observer was unsubscribed before the function was called.
+    assert_eq!(1, calls.get());
+    assert_eq!(Some(0), count_on_unsubscribe.get());
+}
+
+#[test]
+fn flat_map() {
+    let pairs = [[2u8, 2], [3, 3], [5, 5], [7, 7]];
+    let mut indices = &[0usize, 1, 2, 3];
+    let expected = &[2u8, 2, 3, 3, 5, 5, 7, 7];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut flat_mapped = indices.flat_map(|&i| &pairs[i]);
+    flat_mapped.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn flatten() {
+    let mut options = &[Some(2u8), None, Some(3), Some(5)];
+    let mut received = Vec::new();
+    let mut completed = false;
+    options.flatten().subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn concat_map() {
+    let pairs = [[2u8, 2], [3, 3], [5, 5], [7, 7]];
+    let mut indices = &[0usize, 1, 2, 3];
+    let expected = &[2u8, 2, 3, 3, 5, 5, 7, 7];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut concat_mapped = indices.concat_map(|&i| &pairs[i]);
+    concat_mapped.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn concat_map_preserves_order() {
+    let mut source = Subject::<usize, ()>::new();
+    let mut first = Subject::<u8, ()>::new();
+    let mut second = Subject::<u8, ()>::new();
+    let handles = [first.handle(), second.handle()];
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut source_handle = source.handle();
+        let mut concat_mapped = source_handle.concat_map(|i| handles[i].clone());
+        let _subscription = concat_mapped.subscribe_completed(|x| received.push(x), || completed = true);
+        source.on_next(0);
+        source.on_next(1);
+        source.on_completed();
+
+        // The second inner observable is not subscribed to until the first
+        // one completes, so its values are not received.
+        second.on_next(13);
+        first.on_next(2);
+        first.on_completed();
+        second.on_next(3);
+        assert!(!completed);
+        second.on_completed();
+    }
+    assert_eq!(&[2u8, 3][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn flat_map_limit_one_is_concat_map() {
+    let pairs = [[2u8, 2], [3, 3], [5, 5]];
+    let mut indices = &[0usize, 1, 2];
+    let mut limited = Vec::new();
+    let mut concatenated = Vec::new();
+    indices.flat_map_limit(1, |&i| &pairs[i]).subscribe_next(|&x| limited.push(x));
+    indices.concat_map(|&i| &pairs[i]).subscribe_next(|&x| concatenated.push(x));
+    assert_eq!(&[2u8, 2, 3, 3, 5, 5][..], &limited[..]);
+    assert_eq!(&concatenated[..], &limited[..]);
+}
+
+#[test]
+fn flat_map_limit_queues_at_capacity() {
+    let mut source = Subject::<usize, ()>::new();
+    let mut inners = [Subject::<u8, ()>::new(), Subject::new(), Subject::new()];
+    let handles = [inners[0].handle(), inners[1].handle(), inners[2].handle()];
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut source_handle = source.handle();
+        let mut limited = source_handle.flat_map_limit(2, |i| handles[i].clone());
+        let _subscription = limited.subscribe_completed(|x| received.push(x), || completed = true);
+        source.on_next(0);
+        source.on_next(1);
+        source.on_next(2);
+        source.on_completed();
+
+        // The third inner observable is only subscribed to once one of the
+        // first two completes.
+        inners[2].on_next(13);
+        inners[1].on_next(3);
+        inners[0].on_next(2);
+        inners[1].complete();
+        inners[2].on_next(5);
+        inners[0].complete();
+        inners[2].complete();
+    }
+    assert_eq!(&[3u8, 2, 5][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn zip() {
+    let (mut first, mut second) = (&[1u8, 2, 3], &["a", "b"]);
+    let expected = &[(1u8, "a"), (2, "b")];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut zipped = first.zip(&mut second);
+    zipped.subscribe_completed(|(&x, &y)| received.push((x, y)), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn zip_with() {
+    let (mut first, mut second) = (from_vec(vec![1u8, 2, 3]), from_vec(vec![10u8, 20, 30]));
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut zipped = first.zip_with(&mut second, |x, y| x + y);
+    zipped.subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(&[11u8, 22, 33][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn zip_all_truncates() {
+    let mut sources = [&[2u8, 3, 5][..], &[7, 11], &[13, 17, 19, 23]];
+    let mut received = Vec::new();
+    let mut completed = false;
+    zip_all(&mut sources).subscribe_completed(|x| received.push(x), || completed = true);
+    assert_eq!(vec![vec![&2u8, &7, &13], vec![&3, &11, &17]], received);
+    assert!(completed);
+}
+
+#[test]
+fn zip_all_error() {
+    let mut subjects = [Subject::<u8, u32>::new(), Subject::new(), Subject::new()];
+    let mut received = Vec::new();
+    let mut error = None;
+    {
+        let mut sources: Vec<_> = subjects.iter().map(|subject| subject.handle()).collect();
+        let _subscription = zip_all(&mut sources).subscribe_error(
+            |x| received.push(x),
+            || panic!("failed zip should not complete"),
+            |err| error = Some(err)
+        );
+        subjects[0].on_next(2);
+        subjects[1].on_next(3);
+        subjects[2].on_next(5);
+        subjects[1].fail(17);
+
+        // The error drops the subscriptions to the other sources.
+        assert_eq!(0, subjects[0].subscriber_count());
+        assert_eq!(0, subjects[2].subscriber_count());
+    }
+    assert_eq!(vec![vec![2u8, 3, 5]], received);
+    assert_eq!(Some(17), error);
+}
+
+#[test]
+fn zip_all_completed_during_subscribe() {
+    let mut subjects = [Subject::<u8, ()>::new(), Subject::new(), Subject::new()];
+    subjects[1].complete();
+    let mut completed = false;
+    {
+        let mut sources: Vec<_> = subjects.iter().map(|subject| subject.handle()).collect();
+        let _subscription = zip_all(&mut sources).subscribe_completed(
+            |_x| panic!("completed zip should not produce a value"),
+            || completed = true
+        );
+    }
+
+    // The zip completed before the last source was subscribed to, and the
+    // subscription to the first one is dropped.
+    assert_eq!(0, subjects[0].subscriber_count());
+    assert_eq!(0, subjects[2].subscriber_count());
+    assert!(completed);
+}
+
+#[test]
+fn zip_all_empty() {
+    let mut sources: [Option<u8>; 0] = [];
+    let mut completed = false;
+    zip_all(&mut sources).subscribe_completed(
+        |_x| panic!("empty zip should not produce a value"),
+        || completed = true
+    );
+    assert!(completed);
+}
+
+#[test]
+fn buffer_count() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13, 17];
+    let expected = &[vec![2u8, 3, 5], vec![7, 11, 13], vec![17]];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut buffered = values.buffer_count(3);
+    buffered.subscribe_completed(
+        |xs| received.push(xs.into_iter().cloned().collect::<Vec<u8>>()),
+        || completed = true
+    );
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+#[should_panic]
+fn buffer_count_zero() {
+    let mut values = &[2u8, 3, 5];
+    values.buffer_count(0);
+}
+
+#[test]
+fn buffer_boundary() {
+    let mut source = Subject::<u8, ()>::new();
+    let mut boundary = Subject::<(), ()>::new();
+    let mut received = Vec::new();
+    let mut completed = false;
+    let _subscription = {
+        let (mut source_handle, mut boundary_handle) = (source.handle(), boundary.handle());
+        let mut buffered = source_handle.buffer_boundary(&mut boundary_handle);
+        buffered.subscribe_completed(|x| received.push(x), || completed = true)
+    };
+    source.on_next(2);
+    source.on_next(3);
+    boundary.on_next(());
+    boundary.on_next(());
+    source.on_next(5);
+    boundary.on_next(());
+    source.on_next(7);
+    source.on_completed();
+    assert_eq!(vec![vec![2u8, 3], vec![], vec![5], vec![7]], received);
+    assert!(completed);
+}
+
+#[test]
+fn pausable() {
+    let mut source = Subject::<u8, ()>::new();
+    let mut control = Subject::<bool, ()>::new();
+    let mut received = Vec::new();
+    let _subscription = {
+        let (mut source_handle, mut control_handle) = (source.handle(), control.handle());
+        let mut paused = source_handle.pausable(&mut control_handle);
+        paused.subscribe_next(|x| received.push(x))
+    };
+    source.on_next(2);
+    control.on_next(false);
+    source.on_next(3);
+    source.on_next(5);
+    control.on_next(true);
+    source.on_next(7);
+    assert_eq!(&[2u8, 7][..], &received[..]);
+}
+
+#[test]
+fn pausable_initially_paused() {
+    let mut values = &[2u8, 3, 5];
+    let mut control = None;
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut paused = values.pausable_with(&mut control, false);
+    paused.subscribe_completed(|&x| received.push(x), || completed = true);
+    assert!(received.is_empty());
+    assert!(completed);
+}
+
+#[test]
+fn pausable_initially_running() {
+    let mut values = &[2u8, 3, 5];
+    let mut control = None;
+    let mut received = Vec::new();
+    let mut completed = false;
+    {
+        let mut running = values.pausable_with(&mut control, true);
+        running.subscribe_completed(|&x| received.push(x), || completed = true);
+    }
+    assert_eq!(&[2u8, 3, 5][..], &received[..]);
+    assert!(completed);
+
+    // The first control value overrides the initial state.
+    received.clear();
+    let mut control = Some(false);
+    let mut paused = values.pausable_with(&mut control, true);
+    paused.subscribe_next(|&x| received.push(x));
+    assert!(received.is_empty());
+}
+
+#[test]
+fn window_count() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13, 17];
+    let expected = &[vec![2u8, 3, 5], vec![7, 11, 13], vec![17]];
+    let received = RefCell::new(Vec::new());
+    let completed_windows = Cell::new(0);
+    let mut subscriptions = Vec::new();
+    let mut windows = values.window_count(3);
+    windows.subscribe_next(|mut window| {
+        received.borrow_mut().push(Vec::new());
+        let subscription = window.subscribe_completed(
+            |&x| received.borrow_mut().last_mut().unwrap().push(x),
+            || completed_windows.set(completed_windows.get() + 1)
+        );
+        subscriptions.push(subscription);
+    });
+    assert_eq!(&expected[..], &received.borrow()[..]);
+    assert_eq!(3, completed_windows.get());
+}
+
+#[test]
+fn group_by() {
+    let mut values = &[2u8, 3, 5, 8, 13, 21, 34];
+    let received = RefCell::new(Vec::new());
+    let completed_groups = Cell::new(0);
+    let mut subscriptions = Vec::new();
+    let mut groups = values.group_by(|&&x| x % 2 == 0);
+    groups.subscribe_next(|(is_even, mut group)| {
+        let i = received.borrow().len();
+        received.borrow_mut().push((is_even, Vec::new()));
+        let received = &received;
+        let completed_groups = &completed_groups;
+        let subscription = group.subscribe_completed(
+            move |&x| received.borrow_mut()[i].1.push(x),
+            move || completed_groups.set(completed_groups.get() + 1)
+        );
+        subscriptions.push(subscription);
+    });
+    let expected = &[(true, vec![2u8, 8, 34]), (false, vec![3, 5, 13, 21])];
+    assert_eq!(&expected[..], &received.borrow()[..]);
+    assert_eq!(2, completed_groups.get());
+}
+
+#[test]
+fn to_vec() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    let mut completed = false;
+    let mut collected = values.to_vec();
+    collected.subscribe_completed(|xs| received.push(xs), || completed = true);
+    assert_eq!(1, received.len());
+    assert_eq!(&[&2u8, &3, &5, &7, &11, &13][..], &received[0][..]);
+    assert!(completed);
+}
+
+#[test]
+fn all() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    values.all(|&&x| x % 2 == 1).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(false), None][..], &received[..]);
+
+    received.clear();
+    values.all(|&&x| x > 1).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(true), None][..], &received[..]);
+}
+
+#[test]
+fn any() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    values.any(|&&x| x > 10).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(true), None][..], &received[..]);
+
+    received.clear();
+    values.any(|&&x| x > 13).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(false), None][..], &received[..]);
+}
+
+#[test]
+fn contains() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut received = Vec::new();
+    values.contains(&7).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(true), None][..], &received[..]);
+
+    received.clear();
+    values.contains(&9).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(false), None][..], &received[..]);
+}
+
+#[test]
+fn min_max_sum() {
+    let mut values = &[7u32, 3, 13, 2, 11, 5];
+    let mut received = Vec::new();
+    // The method call syntax would resolve to `Ord::min` here.
+    Observable::min(&mut values).subscribe_option(|x| received.push(x.cloned()));
+    assert_eq!(&[Some(2u32), None][..], &received[..]);
+
+    received.clear();
+    Observable::max(&mut values).subscribe_option(|x| received.push(x.cloned()));
+    assert_eq!(&[Some(13u32), None][..], &received[..]);
+
+    let mut owned = values.map(|&x| x);
+    received.clear();
+    owned.sum().subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(41u32), None][..], &received[..]);
+}
+
+#[test]
+fn min_max_sum_empty() {
+    let mut empty: Option<u32> = None;
+    let mut received = Vec::new();
+    Observable::min(&mut empty).subscribe_option(|x| received.push(x));
+    Observable::max(&mut empty).subscribe_option(|x| received.push(x));
+    assert_eq!(&[None, None][..], &received[..]);
+
+    // The sum of no values is zero.
+    empty.sum().subscribe_option(|x| received.push(x));
+    assert_eq!(&[None, None, Some(0), None][..], &received[..]);
+}
+
+#[test]
+fn materialize() {
+    let mut values = &[2u8, 3, 5];
+    let expected = &[Notification::Next(&2u8), Notification::Next(&3), Notification::Next(&5),
+                     Notification::Completed];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.materialize().subscribe_completed(|n| received.push(n), || completed = true);
+    assert_eq!(&expected[..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn materialize_error() {
+    let mut failing: Result<u8, u8> = Err(41);
+    let mut received = Vec::new();
+    let mut completed = false;
+
+    // The materialized observable completes, rather than failing.
+    failing.materialize().subscribe_completed(|n| received.push(n), || completed = true);
+    assert_eq!(&[Notification::Error(41)][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn dematerialize() {
+    let mut notifications = from_vec(vec![
+        Notification::Next(2u8), Notification::Next(3), Notification::<u8, ()>::Completed,
+        Notification::Next(5),
+    ]);
+    let mut received = Vec::new();
+    let mut completed = false;
+    notifications.dematerialize().subscribe_completed(|x| received.push(x), || completed = true);
+
+    // The value after completion should be ignored.
+    assert_eq!(&[2u8, 3][..], &received[..]);
+    assert!(completed);
+}
+
+#[test]
+fn materialize_dematerialize_round_trip() {
+    let mut values = &[2u8, 3, 5, 7];
+    let mut received = Vec::new();
+    let mut completed = false;
+    values.materialize().dematerialize().subscribe_completed(|&x| received.push(x), || completed = true);
+    assert_eq!(&values[..], &received[..]);
+    assert!(completed);
+
+    let mut failing: Result<u8, u8> = Err(41);
+    let mut error = 0;
+    failing.materialize().dematerialize().subscribe_error(
+        |_x| panic!("no value should be pushed"),
+        || panic!("the observable should not complete"),
+        |err| error = err
+    );
+    assert_eq!(41, error);
+}
+
+#[test]
+fn share_subscribes_once() {
+    let mut subject = Subject::<u8, ()>::new();
+    let handle = subject.handle();
+    let subscriptions = Cell::new(0);
+    let mut source = defer(|| {
+        subscriptions.set(subscriptions.get() + 1);
+        handle.clone()
+    });
+    let first = RefCell::new(Vec::new());
+    let second = RefCell::new(Vec::new());
+    let mut shared = source.share();
+    {
+        let _first_subscription = shared.subscribe_next(|x| first.borrow_mut().push(x));
+        let _second_subscription = shared.subscribe_next(|x| second.borrow_mut().push(x));
+        assert_eq!(1, subscriptions.get());
+
+        subject.on_next(2);
+        subject.on_next(3);
+        assert_eq!(&[2u8, 3][..], &first.borrow()[..]);
+        assert_eq!(&[2u8, 3][..], &second.borrow()[..]);
+    }
+
+    // Both subscriptions are dropped, so nothing is pushed anymore, and the
+    // next observer subscribes to the source again.
+    subject.on_next(5);
+    assert_eq!(&[2u8, 3][..], &first.borrow()[..]);
+    let _third_subscription = shared.subscribe_next(|_x| ());
+    assert_eq!(2, subscriptions.get());
+}
+
+#[test]
+fn publish_connect() {
+    let mut values = &[2u8, 3, 5];
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    {
+        let mut published = values.publish();
+        let _first = published.subscribe_next(|&x| first.push(x));
+        let _second = published.subscribe_next(|&x| second.push(x));
+        published.connect();
+    }
+    assert_eq!(&[2u8, 3, 5][..], &first[..]);
+    assert_eq!(&[2u8, 3, 5][..], &second[..]);
+}
+
+#[test]
+fn publish_disconnect() {
+    let mut subject = Subject::<u8, ()>::new();
+    let mut received = Vec::new();
+    {
+        let mut source = subject.handle();
+        let mut published = source.publish();
+        let _subscription = published.subscribe_next(|x| received.push(x));
+        subject.on_next(1);
+        let connection = published.connect();
+        subject.on_next(2);
+        drop(connection);
+        subject.on_next(3);
+    }
+    assert_eq!(&[2u8][..], &received[..]);
+}
+
+#[test]
+fn cache_subscribes_once() {
+    let subscriptions = Cell::new(0);
+    let mut source = defer(|| {
+        subscriptions.set(subscriptions.get() + 1);
+        from_vec(vec![2u8, 3, 5])
+    });
+    let mut cached = source.cache();
+    let mut first = Vec::new();
+    let mut second = Vec::new();
+    let mut completed = false;
+    cached.subscribe_next(|x| first.push(x));
+    cached.subscribe_completed(|x| second.push(x), || completed = true);
+    assert_eq!(1, subscriptions.get());
+    assert_eq!(&[2u8, 3, 5][..], &first[..]);
+    assert_eq!(&[2u8, 3, 5][..], &second[..]);
+    assert!(completed);
+}
+
+#[test]
+fn cache_replays_then_continues() {
+    let mut subject = Subject::<u8, ()>::new();
+    let mut source = subject.handle();
+    let first = RefCell::new(Vec::new());
+    let second = RefCell::new(Vec::new());
+    let mut cached = source.cache();
+    let _first_subscription = cached.subscribe_next(|x| first.borrow_mut().push(x));
+    subject.on_next(2);
+    subject.on_next(3);
+    let _second_subscription = cached.subscribe_next(|x| second.borrow_mut().push(x));
+    assert_eq!(&[2u8, 3][..], &second.borrow()[..]);
+    subject.on_next(5);
+    assert_eq!(&[2u8, 3, 5][..], &first.borrow()[..]);
+    assert_eq!(&[2u8, 3, 5][..], &second.borrow()[..]);
+}
+
+#[test]
+fn sequence_equal() {
+    let (mut first, mut second) = (&[2u8, 3, 5, 7], &[2u8, 3, 5, 7]);
+    let mut received = Vec::new();
+    first.sequence_equal(&mut second).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(true), None][..], &received[..]);
+}
+
+#[test]
+fn sequence_equal_differs() {
+    let (mut first, mut second) = (&[2u8, 3, 5, 7], &[2u8, 3, 6, 7]);
+    let mut received = Vec::new();
+    first.sequence_equal(&mut second).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(false), None][..], &received[..]);
+
+    // A prefix is not equal either.
+    let mut prefix = &[2u8, 3, 5];
+    received.clear();
+    first.sequence_equal(&mut prefix).subscribe_option(|x| received.push(x));
+    assert_eq!(&[Some(false), None][..], &received[..]);
+}
+
+// Boxed tests
+
+#[test]
+fn boxed() {
+    let mut values = &[2u8, 3, 5, 7, 11, 13];
+    let mut doubled = values.map(|&x| x * 2);
+    let large = doubled.filter(|&x| x > 10);
+    let mut observables: Vec<BoxObservable<u8, ()>> = vec![Some(1u8).boxed(), large.boxed()];
+    let expected: [&[u8]; 2] = [&[1], &[14, 22, 26]];
+
+    for (observable, expected) in observables.iter_mut().zip(expected.iter()) {
+        let mut received = Vec::new();
+        let mut completed = false;
+        observable.subscribe_completed(|x| received.push(x), || completed = true);
+        assert_eq!(&expected[..], &received[..]);
+        assert!(completed);
+    }
+}